@@ -3,11 +3,28 @@ extern crate bindgen;
 use std::env;
 use std::path::PathBuf;
 
+/// Directories static libraries are looked up in if pkg-config doesn't know them
+const WELL_KNOWN_LIB_DIRS: &[&str] = &[
+    "/usr/lib64",
+    "/usr/lib",
+    "/usr/lib/x86_64-linux-gnu",
+    "/usr/lib/aarch64-linux-gnu",
+    "/usr/local/lib64",
+    "/usr/local/lib",
+];
+
+/// Libraries static lvm2cmd needs besides devmapper, linked if pkg-config knows them
+const STATIC_DEPS: &[&str] = &["blkid", "libudev", "libsystemd"];
+
 fn main() {
-    // Tell cargo to tell rustc to link the system lvm2app
-    // shared library.
-    println!("cargo:rustc-link-lib=lvm2cmd");
-    println!("cargo:rustc-link-lib=devmapper");
+    if env::var_os("CARGO_FEATURE_STATIC_LVM2").is_some() {
+        link_static();
+    } else {
+        // Tell cargo to tell rustc to link the system lvm2app
+        // shared library.
+        println!("cargo:rustc-link-lib=lvm2cmd");
+        println!("cargo:rustc-link-lib=devmapper");
+    }
 
     let bindings = bindgen::Builder::default()
         // The input header we would like to generate
@@ -30,3 +47,67 @@ fn main() {
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Couldn't write bindings!");
 }
+
+/// Link liblvm2cmd.a and its dependencies, see `static-lvm2` feature.
+/// lvm2cmd has no pkg-config file, so it's looked up in `LVM2_STATIC_LIB_DIR`, devmapper's directories
+/// and well-known ones. Dependencies come from `pkg-config --static`.
+fn link_static() {
+    println!("cargo:rerun-if-env-changed=LVM2_STATIC_LIB_DIR");
+    let devmapper = pkg_config::Config::new()
+        .statik(true)
+        .cargo_metadata(false)
+        .probe("devmapper")
+        .ok();
+
+    let mut dirs: Vec<PathBuf> = env::var_os("LVM2_STATIC_LIB_DIR")
+        .map(PathBuf::from)
+        .into_iter()
+        .collect();
+    if let Some(devmapper) = &devmapper {
+        dirs.extend(devmapper.link_paths.iter().cloned());
+    }
+    dirs.extend(WELL_KNOWN_LIB_DIRS.iter().map(PathBuf::from));
+
+    let lvm2cmd_dir = find_static_lib(&dirs, "lvm2cmd").unwrap_or_else(|| {
+        panic!(
+            "static-lvm2: liblvm2cmd.a not found in {dirs:?}. \
+             Install static LVM2 libraries (e.g. lvm2-devel / device-mapper-devel with static libs, \
+             or build LVM2 with --enable-static_link) or set LVM2_STATIC_LIB_DIR to the directory with liblvm2cmd.a"
+        )
+    });
+    println!("cargo:rustc-link-search=native={}", lvm2cmd_dir.display());
+    println!("cargo:rustc-link-lib=static=lvm2cmd");
+
+    // lvm2cmd goes before its dependencies on the linker command line
+    match pkg_config::Config::new().statik(true).probe("devmapper") {
+        Ok(_devmapper) => (),
+        Err(e) => {
+            let devmapper_dir = find_static_lib(&dirs, "devmapper").unwrap_or_else(|| {
+                panic!(
+                    "static-lvm2: pkg-config failed ({e}) and libdevmapper.a not found in {dirs:?}"
+                )
+            });
+            println!("cargo:rustc-link-search=native={}", devmapper_dir.display());
+            println!("cargo:rustc-link-lib=static=devmapper");
+            println!("cargo:rustc-link-lib=pthread");
+            println!("cargo:rustc-link-lib=m");
+        }
+    }
+    for dep in STATIC_DEPS {
+        // missing ones are fine if LVM2 is built without them
+        if let Err(e) = pkg_config::Config::new().statik(true).probe(dep) {
+            println!("cargo:warning=static-lvm2: {dep} is not linked: {e}");
+        }
+    }
+    if let Some(aio_dir) = find_static_lib(&dirs, "aio") {
+        println!("cargo:rustc-link-search=native={}", aio_dir.display());
+        println!("cargo:rustc-link-lib=static=aio");
+    }
+}
+
+/// Directory with `lib<name>.a`
+fn find_static_lib(dirs: &[PathBuf], name: &str) -> Option<PathBuf> {
+    dirs.iter()
+        .find(|dir| dir.join(format!("lib{name}.a")).is_file())
+        .cloned()
+}
@@ -0,0 +1,84 @@
+//! Audit trail of LVM commands, see [Lvm::enable_audit_log].
+
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::json;
+
+use crate::lvm::{CommandRetCode, Lvm, log_error};
+
+/// File commands are recorded to, see [Lvm::enable_audit_log]
+static AUDIT_LOG: Mutex<Option<File>> = Mutex::new(None);
+
+impl Lvm {
+    /// # Record every command to a file
+    /// Opens the file for appending (creating it if needed) and writes a JSON line per command lvm2cmd runs:
+    /// `{"ts": "2024-01-31T12:00:00.000Z", "cmd": "vgs --reportformat json", "retcode": "CommandSucceeded", "duration_ms": 42}`.
+    /// `cmd` is the full command line, `retcode` is what lvm2cmd returned as [CommandRetCode] in [Debug] form.
+    /// Calling it again switches to another file.
+    ///
+    /// Commands answered by [crate::mock::MockLvm] or rejected before reaching lvm2cmd aren't recorded.
+    /// Failures to write a record are reported to the log sink (see [Lvm::set_log_sink]) or stderr.
+    pub fn enable_audit_log(path: &Path) -> Result<(), std::io::Error> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        *AUDIT_LOG.lock().unwrap_or_else(|e| e.into_inner()) = Some(file);
+        Ok(())
+    }
+
+    /// # Stop recording commands
+    /// Closes the file of [Lvm::enable_audit_log], it's a no-op if there's none
+    pub fn disable_audit_log() {
+        AUDIT_LOG.lock().unwrap_or_else(|e| e.into_inner()).take();
+    }
+}
+
+/// Append the command's record to the audit log if it's enabled
+pub(crate) fn record(command: &str, ret_code: i32, started: SystemTime, duration: Duration) {
+    let mut audit_log = AUDIT_LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(file) = audit_log.as_mut() else {
+        return;
+    };
+    let entry = json!({
+        "ts": iso8601(started),
+        "cmd": command,
+        "retcode": format!("{:?}", CommandRetCode::from(ret_code)),
+        "duration_ms": duration.as_millis() as u64,
+    });
+    if let Err(e) = writeln!(file, "{entry}") {
+        log_error(file!(), line!(), &format!("failed to write audit log: {e}"));
+    }
+}
+
+/// UTC time with milliseconds, e.g. `2024-01-31T12:00:00.000Z`
+fn iso8601(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let secs_of_day = secs % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Gregorian date of the day since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
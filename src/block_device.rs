@@ -0,0 +1,41 @@
+//! Block device checks before destructive commands, see [Lvm::validate_block_device].
+
+use std::{fs, path::Path};
+
+use nix::sys::stat::{SFlag, major, minor, stat};
+
+use crate::lvm::{CommandRetCode, Lvm};
+
+/// # Block device found by [Lvm::validate_block_device]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockDeviceInfo {
+    pub major: u32,
+    pub minor: u32,
+    /// Size of the device, 0 if the kernel doesn't report it
+    pub size_bytes: u64,
+}
+
+impl Lvm {
+    /// # Check that the path is a block device
+    /// Uses `stat(2)` to check the file type, the size comes from `/sys/dev/block/<major>:<minor>/size`,
+    /// so the device isn't opened. Symlinks, e.g. `/dev/disk/by-id/...`, are followed.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if the path doesn't exist or isn't a block device.
+    pub fn validate_block_device(path: &Path) -> Result<BlockDeviceInfo, CommandRetCode> {
+        let st = stat(path).map_err(|_e| CommandRetCode::InvalidParameters)?;
+        if SFlag::from_bits_truncate(st.st_mode) & SFlag::S_IFMT != SFlag::S_IFBLK {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        let (major, minor) = (major(st.st_rdev) as u32, minor(st.st_rdev) as u32);
+        // sysfs reports the size in 512-byte sectors regardless of the sector size of the device
+        let size_bytes = fs::read_to_string(format!("/sys/dev/block/{major}:{minor}/size"))
+            .ok()
+            .and_then(|sectors| sectors.trim().parse::<u64>().ok())
+            .map_or(0, |sectors| sectors * 512);
+        Ok(BlockDeviceInfo {
+            major,
+            minor,
+            size_bytes,
+        })
+    }
+}
@@ -0,0 +1,95 @@
+//! Cache LVs, see [Lvm::create_cache_pool] and `man 7 lvmcache`.
+
+use std::fmt::Display;
+
+use crate::{
+    lv::{LvCreateOptions, LvType, lv_path_arg},
+    lvm::{CommandRetCode, Lvm},
+    units::ByteSize,
+};
+
+/// # dm-cache policy
+/// See "cache policy" in `man 7 lvmcache`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CachePolicy {
+    /// Stochastic multiqueue, the default one
+    Smq,
+    /// Multiqueue, an alias of smq in recent kernels
+    Mq,
+}
+
+impl Display for CachePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Smq => write!(f, "smq"),
+            Self::Mq => write!(f, "mq"),
+        }
+    }
+}
+
+impl Lvm {
+    /// # Create a cache pool
+    /// The first step of caching: the pool is created on fast devices of the VG and then attached with [Lvm::attach_cache].
+    /// LVM picks the metadata size if it's not set.
+    pub fn create_cache_pool(
+        vg: &str,
+        name: &str,
+        size: ByteSize,
+        metadata_size: Option<ByteSize>,
+        policy: CachePolicy,
+    ) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&cache_pool_options(vg, name, size, metadata_size, policy).command()?)
+    }
+
+    /// # Start caching `origin_lv` with `cache_pool`
+    /// Both have to be in the same VG, it runs `lvconvert --yes --type cache --cachepool VG/<pool> VG/<origin>`
+    pub fn attach_cache(vg: &str, origin_lv: &str, cache_pool: &str) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&attach_cache_command(vg, origin_lv, cache_pool)?)
+    }
+
+    /// # Stop caching `lv`
+    /// Dirty blocks are flushed to the origin and the cache pool is removed, it runs `lvconvert --yes --uncache VG/LV`
+    pub fn detach_cache(vg: &str, lv: &str) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&detach_cache_command(vg, lv)?)
+    }
+}
+
+/// `lvcreate` options for a cache pool
+pub(crate) fn cache_pool_options(
+    vg: &str,
+    name: &str,
+    size: ByteSize,
+    metadata_size: Option<ByteSize>,
+    policy: CachePolicy,
+) -> LvCreateOptions {
+    let options = LvCreateOptions::new(vg)
+        .lv_type(LvType::CachePool)
+        .name(name)
+        .size(size)
+        .cache_policy(policy);
+    match metadata_size {
+        Some(metadata_size) => options.pool_metadata_size(metadata_size),
+        None => options,
+    }
+}
+
+/// `lvconvert --yes --type cache --cachepool VG/<pool> VG/<origin>`
+pub(crate) fn attach_cache_command(
+    vg: &str,
+    origin_lv: &str,
+    cache_pool: &str,
+) -> Result<String, CommandRetCode> {
+    Ok(format!(
+        "lvconvert --yes --type cache --cachepool {} {}",
+        lv_path_arg(vg, cache_pool)?,
+        lv_path_arg(vg, origin_lv)?
+    ))
+}
+
+/// `lvconvert --yes --uncache VG/LV`
+pub(crate) fn detach_cache_command(vg: &str, lv: &str) -> Result<String, CommandRetCode> {
+    Ok(format!(
+        "lvconvert --yes --uncache {}",
+        lv_path_arg(vg, lv)?
+    ))
+}
@@ -0,0 +1,139 @@
+//! Builder to assemble LVM command lines from typed arguments instead of raw strings.
+//! Result of [LvmCommandBuilder::build] is a command for [Lvm::run], or it could be passed directly to [Lvm::run_command].
+//!
+//! ```
+//! use lvm_sys2::{command_builder::LvmCommandBuilder, units::ByteSize};
+//! let cmd = LvmCommandBuilder::new()
+//!     .lvcreate()
+//!     .size(ByteSize::from_gib(10))
+//!     .name("mydata")
+//!     .vg("myvg")
+//!     .build();
+//! assert_eq!(cmd.unwrap(), "lvcreate -L 10737418240b -n mydata myvg");
+//! ```
+
+use std::{error::Error, fmt::Display};
+
+use crate::{
+    lvm::{CommandRetCode, Lvm},
+    units::ByteSize,
+};
+
+/// Characters which either have special meaning for shells or break lvm2cmd's command line splitting:
+/// it splits by whitespaces, treats quotes specially and skips everything after `#`
+const FORBIDDEN_CHARS: &[char] = &[
+    '\'', '"', '#', ';', '|', '&', '$', '`', '<', '>', '(', ')', '\\', '*', '?', '!', '{', '}',
+    '[', ']', '~',
+];
+
+/// # Assembles LVM command line
+/// Start from [LvmCommandBuilder::new], pick a command, fill its arguments and [LvmCommandBuilder::build] it.
+#[derive(Debug, Clone, Default)]
+pub struct LvmCommandBuilder {
+    command: Option<&'static str>,
+    size: Option<ByteSize>,
+    name: Option<String>,
+    vg: Option<String>,
+}
+
+/// # Reasons why a command couldn't be built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandBuildError {
+    /// No command was selected
+    MissingCommand,
+    /// Argument required by the command is not set
+    MissingArgument(&'static str),
+    /// Name contains whitespaces or special characters, contains the name
+    InvalidName(String),
+    /// Size of zero was given
+    ZeroSize,
+}
+
+impl Display for CommandBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingCommand => write!(f, "no LVM command selected"),
+            Self::MissingArgument(arg) => write!(f, "missing required argument: {arg}"),
+            Self::InvalidName(name) => write!(f, "invalid name: {name:?}"),
+            Self::ZeroSize => write!(f, "size must not be zero"),
+        }
+    }
+}
+
+impl Error for CommandBuildError {}
+
+impl LvmCommandBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `lvcreate` - requires [LvmCommandBuilder::size] and [LvmCommandBuilder::vg]
+    pub fn lvcreate(mut self) -> Self {
+        self.command = Some("lvcreate");
+        self
+    }
+
+    /// `-L <size>`
+    pub fn size(mut self, size: ByteSize) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// `-n <name>`
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// VG to operate on
+    pub fn vg(mut self, vg: &str) -> Self {
+        self.vg = Some(vg.to_string());
+        self
+    }
+
+    /// # Validate arguments and assemble the command line
+    /// Sizes are passed in bytes to avoid any rounding on LVM side.
+    pub fn build(&self) -> Result<String, CommandBuildError> {
+        let command = self.command.ok_or(CommandBuildError::MissingCommand)?;
+        let mut args = vec![command.to_string()];
+
+        match self.size {
+            Some(ByteSize(0)) => return Err(CommandBuildError::ZeroSize),
+            Some(ByteSize(bytes)) => args.push(format!("-L {bytes}b")),
+            None => return Err(CommandBuildError::MissingArgument("size")),
+        }
+        if let Some(name) = &self.name {
+            args.push(format!("-n {}", validate_name(name)?));
+        }
+        match &self.vg {
+            Some(vg) => args.push(validate_name(vg)?.to_string()),
+            None => return Err(CommandBuildError::MissingArgument("vg")),
+        }
+
+        Ok(args.join(" "))
+    }
+}
+
+impl Lvm {
+    /// # Run command assembled by [LvmCommandBuilder]
+    /// Same as [Lvm::run], returns [CommandRetCode::InvalidParameters] if the command couldn't be built.
+    pub fn run_command(
+        builder: &LvmCommandBuilder,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        let command = builder
+            .build()
+            .map_err(|_e| CommandRetCode::InvalidParameters)?;
+        Self::run(&command)
+    }
+}
+
+fn validate_name(name: &str) -> Result<&str, CommandBuildError> {
+    if name.is_empty()
+        || name
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || FORBIDDEN_CHARS.contains(&c))
+    {
+        return Err(CommandBuildError::InvalidName(name.to_string()));
+    }
+    Ok(name)
+}
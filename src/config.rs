@@ -0,0 +1,287 @@
+//! LVM configuration: the active one with [Lvm::dumpconfig], per call overrides with [Lvm::run_with_config]
+//! and [Lvm::run_with_profile].
+//! `dumpconfig` doesn't support `--reportformat json`, so its config tree output is parsed here.
+
+use std::{collections::BTreeMap, fs, path::Path, str::FromStr};
+
+use crate::lvm::{CommandRetCode, Lvm};
+
+/// # Single configuration value
+/// LVM config tree has integers, floats, strings and arrays of them
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigValue {
+    Int(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<ConfigValue>),
+}
+
+impl ConfigValue {
+    /// Integer settings are used as booleans, e.g. `backup=1`
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Int(i) => Some(*i != 0),
+            _ => None,
+        }
+    }
+
+    /// Strings of a string or an array of strings, e.g. `filter=["a|.*|"]`
+    pub fn as_strings(&self) -> Vec<String> {
+        match self {
+            Self::String(s) => vec![s.clone()],
+            Self::Array(values) => values
+                .iter()
+                .filter_map(|v| match v {
+                    Self::String(s) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+/// # Active LVM configuration
+/// Commonly needed settings are lifted into fields, everything else is in [LvmConfig::values].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LvmConfig {
+    /// `global/use_lvmetad`, None on LVM 2.03+ where lvmetad was removed
+    pub use_lvmetad: Option<bool>,
+    /// `devices/filter`
+    pub devices_filter: Vec<String>,
+    /// `backup/backup`
+    pub backup: Option<bool>,
+    /// All settings by their `section/key` path, e.g. `devices/dir`
+    pub values: BTreeMap<String, ConfigValue>,
+}
+
+impl LvmConfig {
+    /// Setting by its `section/key` path
+    pub fn get(&self, path: &str) -> Option<&ConfigValue> {
+        self.values.get(path)
+    }
+}
+
+impl FromStr for LvmConfig {
+    type Err = CommandRetCode;
+
+    /// Parses config tree output like `global {\n\tumask=63\n}`.
+    /// Line breaks are not required since captured messages are concatenated.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values =
+            parse_tree(s).ok_or_else(|| CommandRetCode::UnexpectedOutput(s.to_string()))?;
+        Ok(Self {
+            use_lvmetad: values
+                .get("global/use_lvmetad")
+                .and_then(ConfigValue::as_bool),
+            devices_filter: values
+                .get("devices/filter")
+                .map(ConfigValue::as_strings)
+                .unwrap_or_default(),
+            backup: values.get("backup/backup").and_then(ConfigValue::as_bool),
+            values,
+        })
+    }
+}
+
+impl Lvm {
+    /// # Get the active LVM configuration
+    /// Runs `dumpconfig --typeconfig full`, so settings which aren't set explicitly are reported with their defaults.
+    /// `--typeconfig full` requires LVM 2.02.119 or newer.
+    ///
+    /// Returns [CommandRetCode::UnexpectedOutput] with the whole output if it couldn't be parsed.
+    pub fn dumpconfig() -> Result<LvmConfig, CommandRetCode> {
+        // dumpconfig doesn't support --reportformat
+        Self::run_without_flags("dumpconfig --typeconfig full")?.parse()
+    }
+
+    /// # Run LVM command with settings from a config file
+    /// Same as [Lvm::run], but the file's content is passed with `--config`, e.g. to point tests to scratch devices.
+    /// Settings of the file override ones of `lvm.conf`, which is still read.
+    /// The setting of [Lvm::set_udev_sync] goes into the same `--config` unless the file has its own `udev_sync`.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if the file can't be read or contains `'`, which can't be quoted.
+    pub fn run_with_config(
+        command: &str,
+        config_path: &Path,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        let config =
+            fs::read_to_string(config_path).map_err(|_e| CommandRetCode::InvalidParameters)?;
+        if config.contains('\'') {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        // LVM takes a single --config, settings of the file win over udev sync ones
+        let config = match Self::udev_sync_config() {
+            Some(udev_sync) if !config.contains("udev_sync") => {
+                format!("{udev_sync} {}", config.trim())
+            }
+            _ => config.trim().to_string(),
+        };
+        Self::run(&format!("{command} --config '{config}'"))
+    }
+
+    /// # Run LVM command with a configuration profile
+    /// Same as [Lvm::run] with `--profile <profile>`, settings of the profile overlay `lvm.conf`.
+    /// See [Lvm::list_profiles] for available ones.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if the name isn't alphanumeric + `_`.
+    pub fn run_with_profile(
+        command: &str,
+        profile: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        if profile.is_empty()
+            || !profile
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        Self::run(&format!("{command} --profile {profile}"))
+    }
+
+    /// # Names of configuration profiles in `/etc/lvm/profile`
+    /// Profiles are `<name>.profile` files, other files are skipped. No LVM command is issued.
+    /// Names are sorted.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if the directory can't be read.
+    pub fn list_profiles() -> Result<Vec<String>, CommandRetCode> {
+        profiles_in(Path::new(PROFILE_DIR))
+    }
+}
+
+/// Default `config/profile_dir`
+const PROFILE_DIR: &str = "/etc/lvm/profile";
+
+/// Names of configuration profiles in the directory, see [Lvm::list_profiles]
+fn profiles_in(dir: &Path) -> Result<Vec<String>, CommandRetCode> {
+    let mut profiles = vec![];
+    for entry in fs::read_dir(dir).map_err(|_e| CommandRetCode::InvalidParameters)? {
+        let path = entry
+            .map_err(|_e| CommandRetCode::InvalidParameters)?
+            .path();
+        if path.extension().is_some_and(|ext| ext == "profile")
+            && let Some(name) = path.file_stem().and_then(|name| name.to_str())
+        {
+            profiles.push(name.to_string());
+        }
+    }
+    profiles.sort();
+    Ok(profiles)
+}
+
+/// Config tree token
+#[derive(Debug)]
+enum Token<'a> {
+    Word(&'a str),
+    Quoted(String),
+    Punct(char),
+}
+
+const PUNCTUATION: &[char] = &['{', '}', '[', ']', '=', ','];
+
+fn tokenize(s: &str) -> Option<Vec<Token<'_>>> {
+    let mut tokens = vec![];
+    let mut chars = s.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => (),
+            c if PUNCTUATION.contains(&c) => tokens.push(Token::Punct(c)),
+            '"' => {
+                let mut quoted = String::new();
+                loop {
+                    match chars.next()? {
+                        (_, '"') => break,
+                        (_, '\\') => quoted.push(chars.next()?.1),
+                        (_, c) => quoted.push(c),
+                    }
+                }
+                tokens.push(Token::Quoted(quoted));
+            }
+            _ => {
+                let mut end = start + c.len_utf8();
+                while let Some((idx, c)) = chars
+                    .next_if(|(_, c)| !c.is_whitespace() && !PUNCTUATION.contains(c) && *c != '"')
+                {
+                    end = idx + c.len_utf8();
+                }
+                tokens.push(Token::Word(&s[start..end]));
+            }
+        }
+    }
+    Some(tokens)
+}
+
+/// Flatten config tree into `section/key` paths
+fn parse_tree(s: &str) -> Option<BTreeMap<String, ConfigValue>> {
+    let mut values = BTreeMap::new();
+    let mut sections: Vec<&str> = vec![];
+    let mut tokens = tokenize(s)?.into_iter();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Punct('}') => {
+                sections.pop()?;
+            }
+            Token::Word(name) => match tokens.next()? {
+                Token::Punct('{') => sections.push(name),
+                Token::Punct('=') => {
+                    let value = match tokens.next()? {
+                        Token::Punct('[') => {
+                            let mut items = vec![];
+                            loop {
+                                match tokens.next()? {
+                                    Token::Punct(']') => break,
+                                    Token::Punct(',') => (),
+                                    token => items.push(scalar(token)?),
+                                }
+                            }
+                            ConfigValue::Array(items)
+                        }
+                        token => scalar(token)?,
+                    };
+                    let path = sections
+                        .iter()
+                        .chain(std::iter::once(&name))
+                        .copied()
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    values.insert(path, value);
+                }
+                _ => return None,
+            },
+            _ => return None,
+        }
+    }
+    sections.is_empty().then_some(values)
+}
+
+fn scalar(token: Token<'_>) -> Option<ConfigValue> {
+    match token {
+        Token::Quoted(s) => Some(ConfigValue::String(s)),
+        Token::Word(w) => w
+            .parse()
+            .map(ConfigValue::Int)
+            .or_else(|_e| w.parse().map(ConfigValue::Float))
+            .ok(),
+        Token::Punct(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_profiles() {
+        let dir = std::env::temp_dir().join("lvm-sys2-profiles");
+        fs::create_dir_all(&dir).unwrap();
+        for file in ["thin_performance.profile", "cache.profile", "README"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        assert_eq!(profiles_in(&dir).unwrap(), ["cache", "thin_performance"]);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            profiles_in(&dir).unwrap_err(),
+            CommandRetCode::InvalidParameters
+        );
+    }
+}
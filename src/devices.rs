@@ -0,0 +1,78 @@
+//! Restricting devices LVM looks at, see [Lvm::run_on_devices] and `man 8 lvmdevices`.
+
+use std::path::{Component, Path};
+
+use crate::{
+    lv::quote,
+    lvm::{CommandRetCode, Lvm},
+};
+
+/// Directory devices files live in, `--devicesfile` takes a file name in it
+const DEVICES_DIR: &str = "/etc/lvm/devices";
+
+impl Lvm {
+    /// # Run LVM command on the given devices only
+    /// Same as [Lvm::run] with `--devices <dev1>,<dev2>,...`, LVM doesn't scan other devices,
+    /// which is much faster on systems with lots of them.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if the list is empty or a path isn't under `/dev/`.
+    /// Devices aren't checked on the filesystem, LVM ignores missing ones.
+    pub fn run_on_devices(
+        command: &str,
+        devices: &[&Path],
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        Self::run(&format!("{command} {}", devices_option(devices)?))
+    }
+
+    /// # Run LVM command with another devices file
+    /// Same as [Lvm::run] with `--devicesfile <name>`, LVM only uses devices listed in the file.
+    /// The file has to be in `/etc/lvm/devices`, either its name or full path could be passed.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] for files elsewhere.
+    pub fn run_with_devicesfile(
+        command: &str,
+        devices_file: &Path,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        Self::run(&format!("{command} {}", devicesfile_option(devices_file)?))
+    }
+}
+
+/// # `--devices` option
+/// See [Lvm::run_on_devices]
+pub(crate) fn devices_option(devices: &[&Path]) -> Result<String, CommandRetCode> {
+    if devices.is_empty() {
+        return Err(CommandRetCode::InvalidParameters);
+    }
+    let devices = devices
+        .iter()
+        .map(|device| match device.to_str() {
+            Some(s)
+                if s.len() > "/dev/".len()
+                    && s.starts_with("/dev/")
+                    && device.components().all(|c| c != Component::ParentDir)
+                    && !s.contains(|c: char| c.is_whitespace() || c == ',' || c == '\'') =>
+            {
+                Ok(s)
+            }
+            _ => Err(CommandRetCode::InvalidParameters),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("--devices {}", quote(&devices.join(","))))
+}
+
+/// # `--devicesfile` option
+/// See [Lvm::run_with_devicesfile]
+pub(crate) fn devicesfile_option(devices_file: &Path) -> Result<String, CommandRetCode> {
+    let in_devices_dir = match devices_file.parent() {
+        Some(parent) => parent.as_os_str().is_empty() || parent == Path::new(DEVICES_DIR),
+        None => false,
+    };
+    match devices_file.file_name().and_then(|name| name.to_str()) {
+        Some(name)
+            if in_devices_dir && !name.contains(|c: char| c.is_whitespace() || c == '\'') =>
+        {
+            Ok(format!("--devicesfile {}", quote(name)))
+        }
+        _ => Err(CommandRetCode::InvalidParameters),
+    }
+}
@@ -0,0 +1,96 @@
+//! Safe iteration over libdevmapper's intrusive `struct dm_list`, see [DmList].
+//! The module isn't named `dm_list` as the bindings' struct takes the name at the crate root.
+
+use std::{marker::PhantomData, ptr};
+
+use crate::{dm_list, dm_list_end, dm_list_first, dm_list_next};
+
+/// # Borrowed `struct dm_list` of `T`s
+/// libdevmapper lists are intrusive: each element embeds a `dm_list` link, e.g. [crate::dm_str_list::list],
+/// and the head is a standalone `dm_list`. The list is borrowed for `'a`, so elements can't outlive the allocation.
+///
+/// ```
+/// use std::{mem::offset_of, ptr};
+/// use lvm_sys2::{dm_list, dm_str_list, dmlist::DmList};
+///
+/// let mut head = dm_list { n: ptr::null_mut(), p: ptr::null_mut() };
+/// let head_ptr: *mut dm_list = &mut head;
+/// head.n = head_ptr;
+/// head.p = head_ptr;
+/// // SAFETY: the empty head points to itself, `list` is how dm_str_list is linked
+/// let list = unsafe { DmList::<dm_str_list>::new(&head, offset_of!(dm_str_list, list)) };
+/// assert_eq!(list.iter().count(), 0);
+/// ```
+pub struct DmList<'a, T> {
+    head: &'a dm_list,
+    /// offset of the `dm_list` link in `T`
+    offset: usize,
+    _items: PhantomData<&'a T>,
+}
+
+impl<'a, T> DmList<'a, T> {
+    /// # Wrap a list head
+    /// `offset` is where the link is in `T`, i.e. `std::mem::offset_of!(T, <link field>)`.
+    ///
+    /// # Safety
+    /// `head` must be a properly linked list (an empty one points to itself),
+    /// every element must be a `T` with its link at `offset`, and the list must not change while borrowed.
+    pub unsafe fn new(head: &'a dm_list, offset: usize) -> Self {
+        Self {
+            head,
+            offset,
+            _items: PhantomData,
+        }
+    }
+
+    /// # Iterate over the elements
+    pub fn iter(&self) -> DmListIter<'a, T> {
+        let head: *const dm_list = self.head;
+        DmListIter {
+            head,
+            // SAFETY: the head is valid, see new()
+            next: unsafe { dm_list_first(head) },
+            offset: self.offset,
+            _items: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &DmList<'a, T> {
+    type Item = &'a T;
+    type IntoIter = DmListIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// # Iterator of [DmList::iter]
+/// Stops at a NULL link as well as at the end of the list
+pub struct DmListIter<'a, T> {
+    head: *const dm_list,
+    /// link of the next element, NULL when done
+    next: *mut dm_list,
+    offset: usize,
+    _items: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for DmListIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let link = self.next;
+        if link.is_null() || ptr::eq(link, self.head) {
+            return None;
+        }
+        // SAFETY: links are valid for 'a and belong to T's at the offset, see DmList::new()
+        unsafe {
+            self.next = if dm_list_end(self.head, link) != 0 {
+                ptr::null_mut()
+            } else {
+                dm_list_next(self.head, link)
+            };
+            Some(&*link.cast::<u8>().sub(self.offset).cast::<T>())
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Domain-specific errors recognized from LVM messages, see [Lvm::run_checked],
+//! and errors of name validation, see [crate::vg::vg_validate_name].
+
+use std::{error::Error, fmt::Display};
+
+use crate::lvm::{CommandRetCode, Lvm};
+
+/// # Failure of [Lvm::run_checked]
+/// LVM reports most failures as [CommandRetCode::ProcessingFailed], the reason is only in its error messages.
+/// Known ones are turned into variants, which contain the message they were recognized from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LvmError {
+    /// Device is opened by someone else, e.g. `Can't open /dev/sdb exclusively.  Mounted filesystem?`
+    DeviceBusy(String),
+    /// e.g. `Cannot use /dev/sdz: device not found`
+    DeviceNotFound(String),
+    /// e.g. `Volume group "vg0" not found`
+    VgNotFound(String),
+    /// e.g. `Failed to find logical volume "vg0/lv0"`
+    LvNotFound(String),
+    /// e.g. `Physical volume '/dev/sdb' is already in volume group 'vg0'`
+    PvInUse(String),
+    /// e.g. `Volume group "vg0" has insufficient free space (10 extents): 256 required.`
+    InsufficientFreeSpace(String),
+    /// Another command holds the lock, e.g. `Can't get lock for vg0`
+    LockFailed(String),
+    /// Anything else, contains the original error and all FATAL / ERROR messages of the command
+    Other {
+        code: CommandRetCode,
+        messages: Vec<String>,
+    },
+}
+
+impl LvmError {
+    /// # Recognize the error by LVM messages
+    /// The first message matching a known pattern wins, [LvmError::Other] is returned if none does.
+    pub fn classify(code: CommandRetCode, messages: Vec<String>) -> Self {
+        let recognized = messages.iter().find_map(|message| {
+            let m = message.trim();
+            let variant: fn(String) -> Self =
+                if m.starts_with("Can't open ") && m.contains(" exclusively") {
+                    Self::DeviceBusy
+                } else if m.contains("device not found") || m.starts_with("No device found for ") {
+                    Self::DeviceNotFound
+                } else if m.starts_with("Volume group ") && m.contains(" not found") {
+                    Self::VgNotFound
+                } else if m.starts_with("Failed to find logical volume ") {
+                    Self::LvNotFound
+                } else if m.starts_with("Physical volume ")
+                    && (m.contains(" is already in volume group ") || m.contains(" is used by VG "))
+                {
+                    Self::PvInUse
+                } else if m.to_lowercase().contains("insufficient free space") {
+                    Self::InsufficientFreeSpace
+                } else if m.starts_with("Can't get lock ") || m.contains("is locked by another") {
+                    Self::LockFailed
+                } else {
+                    return None;
+                };
+            Some(variant(m.to_string()))
+        });
+        recognized.unwrap_or(Self::Other { code, messages })
+    }
+}
+
+impl From<CommandRetCode> for LvmError {
+    fn from(code: CommandRetCode) -> Self {
+        Self::Other {
+            code,
+            messages: vec![],
+        }
+    }
+}
+
+impl Display for LvmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DeviceBusy(m) => write!(f, "device is busy: {m}"),
+            Self::DeviceNotFound(m) => write!(f, "device not found: {m}"),
+            Self::VgNotFound(m) => write!(f, "volume group not found: {m}"),
+            Self::LvNotFound(m) => write!(f, "logical volume not found: {m}"),
+            Self::PvInUse(m) => write!(f, "physical volume is in use: {m}"),
+            Self::InsufficientFreeSpace(m) => write!(f, "insufficient free space: {m}"),
+            Self::LockFailed(m) => write!(f, "failed to get LVM lock: {m}"),
+            Self::Other { code, messages } if messages.is_empty() => write!(f, "{code}"),
+            Self::Other { code, messages } => write!(f, "{code}: {}", messages.join("; ")),
+        }
+    }
+}
+
+/// `{"kind": "<variant>", "message": "..."}`, [LvmError::Other] is `{"kind": "Other", "code": ..., "messages": [...]}`
+/// where `code` is the serialized [CommandRetCode]
+#[cfg(feature = "serde")]
+impl serde::Serialize for LvmError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let (kind, message) = match self {
+            Self::DeviceBusy(m) => ("DeviceBusy", m),
+            Self::DeviceNotFound(m) => ("DeviceNotFound", m),
+            Self::VgNotFound(m) => ("VgNotFound", m),
+            Self::LvNotFound(m) => ("LvNotFound", m),
+            Self::PvInUse(m) => ("PvInUse", m),
+            Self::InsufficientFreeSpace(m) => ("InsufficientFreeSpace", m),
+            Self::LockFailed(m) => ("LockFailed", m),
+            Self::Other { code, messages } => {
+                let mut map = serializer.serialize_map(Some(3))?;
+                map.serialize_entry("kind", "Other")?;
+                map.serialize_entry("code", code)?;
+                map.serialize_entry("messages", messages)?;
+                return map.end();
+            }
+        };
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("kind", kind)?;
+        map.serialize_entry("message", message)?;
+        map.end()
+    }
+}
+
+impl Error for LvmError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Other { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+}
+
+impl Lvm {
+    /// # Run LVM command recognizing the failure reason
+    /// Same as [Lvm::run], but a failed command's error messages are inspected, see [LvmError::classify].
+    /// Errors of the wrapper itself, e.g. [CommandRetCode::InitFailed], are [LvmError::Other] without messages.
+    pub fn run_checked(
+        command: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, LvmError> {
+        Self::run_with_errors(command)
+            .map_err(|(code, messages)| LvmError::classify(code, messages))
+    }
+}
+
+/// # Why a VG / LV name is invalid
+/// See [crate::vg::vg_validate_name] and [crate::lv::lv_validate_name]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameValidationError {
+    Empty,
+    TooLong {
+        max: usize,
+        actual: usize,
+    },
+    /// Characters LVM doesn't allow, in the order they appear, or `-` if the name starts with it
+    InvalidChars(String),
+    /// `.` / `..`, or what LVM reserves for internal LVs, e.g. `snapshot` prefix or `_tmeta` substring
+    ReservedWord(&'static str),
+}
+
+impl Display for NameValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "name is empty"),
+            Self::TooLong { max, actual } => {
+                write!(
+                    f,
+                    "name is too long: {actual} characters, at most {max} allowed"
+                )
+            }
+            Self::InvalidChars(chars) => write!(f, "name contains invalid characters: {chars:?}"),
+            Self::ReservedWord(word) => write!(f, "name uses reserved word {word:?}"),
+        }
+    }
+}
+
+impl Error for NameValidationError {}
+
+/// Commands reject invalid names with [CommandRetCode::InvalidParameters]
+impl From<NameValidationError> for CommandRetCode {
+    fn from(_e: NameValidationError) -> Self {
+        Self::InvalidParameters
+    }
+}
@@ -0,0 +1,81 @@
+//! Historical LVs, records LVM keeps about removed thin LVs, see [Lvm::lvs_historical] and
+//! `record_lvs_history` in lvm.conf.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    lv::quote,
+    lvm::{CommandRetCode, Lvm},
+    report::{Report, parse_report},
+    version::LvmVersion,
+    vg::validate_vg_name,
+};
+
+/// First LVM version with historical LVs
+pub const MIN_HISTORY_VERSION: LvmVersion = LvmVersion::new(2, 2, 175);
+
+/// Fields [Lvm::lvs_historical] asks for
+const HISTORY_FIELDS: &str = "lv_name,vg_name,lv_time_removed,lv_uuid,lv_historical";
+
+/// # Removed LV as reported by `lvs --history`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct HistoricalLvInfo {
+    /// LVM prefixes names of historical LVs with `-`, it's stripped here
+    pub lv_name: String,
+    pub vg_name: String,
+    /// As LVM prints it, e.g. `2024-01-31 12:00:00 +0000`
+    pub lv_time_removed: String,
+    pub lv_uuid: String,
+}
+
+/// `lvs --history` row, live LVs are reported along with historical ones
+#[derive(Deserialize)]
+struct HistoryRow {
+    #[serde(flatten)]
+    info: HistoricalLvInfo,
+    lv_historical: String,
+}
+
+#[derive(Deserialize)]
+struct HistoryReport {
+    lv: Vec<HistoryRow>,
+}
+
+impl Lvm {
+    /// # List historical LVs of a VG
+    /// Runs `lvs -H -o lv_name,vg_name,lv_time_removed,lv_uuid,lv_historical VG` and keeps historical LVs only.
+    /// LVM records them for removed thin LVs if `metadata/record_lvs_history` is enabled in lvm.conf,
+    /// the list is empty otherwise.
+    ///
+    /// Returns [CommandRetCode::FeatureNotSupported] if LVM is older than [MIN_HISTORY_VERSION].
+    pub fn lvs_historical(vg: &str) -> Result<Vec<HistoricalLvInfo>, CommandRetCode> {
+        let command = lvs_historical_command(vg)?;
+        if Lvm::version()? < MIN_HISTORY_VERSION {
+            return Err(CommandRetCode::FeatureNotSupported);
+        }
+        let reports: Report<HistoryReport> = parse_report(Value::Object(Lvm::run(&command)?))?;
+        Ok(reports
+            .report
+            .into_iter()
+            .flat_map(|r| r.lv)
+            // binary fields are the field name or 1 if set, empty or 0 otherwise
+            .filter(|row| !row.lv_historical.is_empty() && row.lv_historical != "0")
+            .map(|mut row| {
+                if let Some(name) = row.info.lv_name.strip_prefix('-') {
+                    row.info.lv_name = name.to_string();
+                }
+                row.info
+            })
+            .collect())
+    }
+}
+
+/// # `lvs --history` command
+/// See [Lvm::lvs_historical]
+pub(crate) fn lvs_historical_command(vg: &str) -> Result<String, CommandRetCode> {
+    Ok(format!(
+        "lvs -H -o {HISTORY_FIELDS} {}",
+        quote(validate_vg_name(vg)?)
+    ))
+}
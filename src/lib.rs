@@ -5,5 +5,33 @@
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
+pub mod audit;
+#[cfg(feature = "nix")]
+pub mod block_device;
+pub mod cache;
+pub mod command_builder;
+pub mod config;
+pub mod devices;
+pub mod dmlist;
+pub mod error;
+pub mod history;
+pub mod lock;
+pub mod lv;
 #[allow(rustdoc::private_intra_doc_links)]
 pub mod lvm;
+#[cfg(feature = "test-utils")]
+pub mod mock;
+pub mod monitor;
+pub mod pagination;
+pub mod partial;
+pub mod pv;
+pub mod report;
+pub mod retry;
+pub mod sinks;
+pub mod snapshot;
+pub mod tags;
+pub mod thin;
+pub mod units;
+pub mod vdo;
+pub mod version;
+pub mod vg;
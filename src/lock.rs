@@ -0,0 +1,115 @@
+//! VG locks of shared VGs in clustered setups, see [Lvm::vg_lock], [Lvm::run_with_lockopt] and `man 8 lvmlockd`.
+
+use std::fmt::Display;
+
+use crate::{
+    lv::quote,
+    lvm::{CommandRetCode, Lvm, log_error},
+    vg::validate_vg_name,
+};
+
+/// # Mode of a VG lock
+/// Displayed as lvmlockd names them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LockMode {
+    /// `sh` - other hosts may hold it too, enough for reading
+    Shared,
+    /// `ex` - only this host holds it, needed for changes
+    Exclusive,
+}
+
+impl Display for LockMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Shared => write!(f, "sh"),
+            Self::Exclusive => write!(f, "ex"),
+        }
+    }
+}
+
+impl Lvm {
+    /// # Lock a shared VG until the guard is dropped
+    /// Runs `vgchange --lock-opt <mode> VG`, the returned [VgLockGuard] runs `vgchange --lock-opt none VG` on drop.
+    ///
+    /// Returns [CommandRetCode::LockManagerNotAvailable] if LVM complains about `lvmlockd`, e.g. it's not running.
+    pub fn vg_lock(vg: &str, mode: LockMode) -> Result<VgLockGuard, CommandRetCode> {
+        run_lock_command(&vg_lock_command(vg, Some(mode))?)?;
+        Ok(VgLockGuard { vg: vg.to_string() })
+    }
+
+    /// # Release a VG lock
+    /// Runs `vgchange --lock-opt none VG`, the same as dropping [VgLockGuard] does,
+    /// for locks which outlived their guard, e.g. after [std::mem::forget].
+    pub fn vg_unlock(vg: &str) -> Result<(), CommandRetCode> {
+        run_lock_command(&vg_lock_command(vg, None)?)
+    }
+
+    /// # Run LVM command with a lvmlockd lock option
+    /// Same as [Lvm::run] with `--lockopt <lockopt>`, one of [LOCK_OPTS]:
+    /// - `skiplv` / `skipvg` - don't take LV / VG locks
+    /// - `force` - override lock checks
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] for other values.
+    pub fn run_with_lockopt(
+        command: &str,
+        lockopt: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        if !LOCK_OPTS.contains(&lockopt) {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        Self::run(&format!("{command} --lockopt {lockopt}"))
+    }
+
+    /// # Run LVM query without taking locks
+    /// Same as [Lvm::run] with `--lockopt skip`, for read-only queries where `lvmlockd` is unreachable.
+    /// The data may be changed by other hosts meanwhile.
+    pub fn run_without_lock(
+        command: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        Self::run(&format!("{command} --lockopt skip"))
+    }
+}
+
+/// Values of [Lvm::run_with_lockopt]
+pub const LOCK_OPTS: &[&str] = &["skiplv", "skipvg", "force"];
+
+/// # `vgchange --lock-opt` command
+/// `None` releases the lock, see [Lvm::vg_lock]
+pub(crate) fn vg_lock_command(vg: &str, mode: Option<LockMode>) -> Result<String, CommandRetCode> {
+    let mode = mode.map_or("none".to_string(), |m| m.to_string());
+    Ok(format!(
+        "vgchange --lock-opt {mode} {}",
+        quote(validate_vg_name(vg)?)
+    ))
+}
+
+/// Run the command recognizing `lvmlockd` failures
+fn run_lock_command(command: &str) -> Result<(), CommandRetCode> {
+    Lvm::run_quiet_with_errors(command).map_err(|(code, messages)| {
+        if messages.iter().any(|m| m.contains("lvmlockd")) {
+            CommandRetCode::LockManagerNotAvailable
+        } else {
+            code
+        }
+    })
+}
+
+/// # Held VG lock
+/// Obtained with [Lvm::vg_lock], releases the lock when dropped.
+/// Errors can't be returned from [Drop], so they are reported to the log sink (see [Lvm::set_log_sink]) or stderr.
+#[derive(Debug)]
+pub struct VgLockGuard {
+    vg: String,
+}
+
+impl Drop for VgLockGuard {
+    fn drop(&mut self) {
+        if let Err(e) = Lvm::vg_unlock(&self.vg) {
+            log_error(
+                file!(),
+                line!(),
+                &format!("failed to unlock {}: {e}", self.vg),
+            );
+        }
+    }
+}
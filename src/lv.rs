@@ -0,0 +1,695 @@
+//! Logical volumes management, see [LvManager].
+
+use std::{fmt::Display, path::Path};
+
+use crate::{
+    cache::CachePolicy,
+    error::NameValidationError,
+    lvm::{CommandRetCode, Lvm, log_error},
+    pv::device_arg,
+    report::{lvs_from, vgs_from},
+    units::ByteSize,
+    vdo::VdoLvOptions,
+    vg::{MAX_NAME_LEN, validate_vg_name, vg_validate_name},
+};
+
+/// Prefixes LVM reserves for internal LVs
+const RESERVED_LV_PREFIXES: &[&str] = &["snapshot", "pvmove"];
+/// Substrings LVM reserves for sub-LVs
+const RESERVED_LV_SUBSTRINGS: &[&str] = &[
+    "_cdata", "_cmeta", "_corig", "_mlog", "_mimage", "_pmspare", "_rimage", "_rmeta", "_tdata",
+    "_tmeta", "_vorigin", "_vdata",
+];
+
+/// # Typed wrappers for LV commands
+/// Obtained with [Lvm::lv].
+/// VG / LV names are validated before running any command, [CommandRetCode::InvalidParameters] is returned for invalid ones.
+/// Names are quoted in the command line, so lvm2cmd doesn't interpret them in any way.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LvManager;
+
+impl Lvm {
+    /// Access LV commands
+    pub fn lv() -> LvManager {
+        LvManager
+    }
+
+    /// # Activate `VG/LV`
+    /// Shortcut for `lvchange -ay`, returns [CommandRetCode::InvalidParameters] for invalid names, e.g. ones with `/`
+    pub fn activate_lv(vg: &str, lv: &str) -> Result<(), CommandRetCode> {
+        Self::lv().lvchange(vg, lv, &LvChangeOptions::new().activate(true))
+    }
+
+    /// # Deactivate `VG/LV`
+    /// Shortcut for `lvchange -an`, see [Lvm::activate_lv]
+    pub fn deactivate_lv(vg: &str, lv: &str) -> Result<(), CommandRetCode> {
+        Self::lv().lvchange(vg, lv, &LvChangeOptions::new().activate(false))
+    }
+
+    /// # Activate `VG/LV` until the guard is dropped
+    /// The returned [LvmTransaction] deactivates the LV on drop, including unwinding and early returns.
+    pub fn activate_scoped(vg: &str, lv: &str) -> Result<LvmTransaction, CommandRetCode> {
+        Self::activate_lv(vg, lv)?;
+        Ok(LvmTransaction {
+            vg: vg.to_string(),
+            lv: lv.to_string(),
+            keep_active: false,
+        })
+    }
+
+    /// # Replace failed images of a RAID LV
+    /// Runs `lvconvert --repair --yes VG/LV <replacement>`, the replacement device has to be a PV of the VG
+    pub fn lvconvert_raid_repair(
+        vg: &str,
+        lv: &str,
+        replacement: &Path,
+    ) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!(
+            "lvconvert --repair --yes {} {}",
+            lv_path_arg(vg, lv)?,
+            device_arg(replacement)?
+        ))
+    }
+
+    /// # Add mirror images to an LV
+    /// Runs `lvconvert --yes -m <mirrors> VG/LV [pvs...]`, images are allocated on `pvs` if any are given.
+    /// `mirrors` is the number of additional images, LVM accepts 1 to 4.
+    ///
+    /// Syncing the images is a long-running operation, which blocks till it's done.
+    /// In interactive contexts consider [Lvm::run_with_timeout] with the same command line.
+    pub fn lvconvert_to_mirror(
+        vg: &str,
+        lv: &str,
+        mirrors: u8,
+        pvs: &[&Path],
+    ) -> Result<(), CommandRetCode> {
+        if !(1..=4).contains(&mirrors) {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        let mut command = format!("lvconvert --yes -m {mirrors} {}", lv_path_arg(vg, lv)?);
+        for pv in pvs {
+            command.push(' ');
+            command.push_str(device_arg(pv)?);
+        }
+        Lvm::run_quiet(&command)
+    }
+
+    /// # Remove all mirror images of an LV
+    /// Runs `lvconvert --yes -m 0 VG/LV`, see [Lvm::lvconvert_to_mirror]
+    pub fn lvconvert_to_linear(vg: &str, lv: &str) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!("lvconvert --yes -m 0 {}", lv_path_arg(vg, lv)?))
+    }
+
+    /// # Extend `VG/LV` to all free space of the VG
+    /// Runs `lvextend -l +100%FREE VG/LV`.
+    /// Returns [CommandRetCode::InsufficientFreeSpace] if `vgs` reports no free space in the VG.
+    pub fn lvextend_to_fill_vg(vg: &str, lv: &str) -> Result<(), CommandRetCode> {
+        let lv_arg = lv_path_arg(vg, lv)?;
+        let command = format!("vgs {}", quote(vg));
+        let info = vgs_from(&command)?
+            .into_iter()
+            .next()
+            .ok_or(CommandRetCode::UnexpectedOutput(command))?;
+        if info.vg_free == ByteSize(0) {
+            return Err(CommandRetCode::InsufficientFreeSpace);
+        }
+        Lvm::run_quiet(&format!("lvextend -l +100%FREE {lv_arg}"))
+    }
+
+    /// # Extend `VG/LV` by `amount`
+    /// Runs `lvextend -L +<amount> VG/LV`, zero is rejected
+    pub fn lvextend_by(vg: &str, lv: &str, amount: ByteSize) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!(
+            "lvextend -L +{} {}",
+            size_arg(amount)?,
+            lv_path_arg(vg, lv)?
+        ))
+    }
+
+    /// # Extend `VG/LV` to `total` size
+    /// Runs `lvextend -L <total> VG/LV`, zero is rejected. LVM fails if the LV is already bigger.
+    pub fn lvextend_to(vg: &str, lv: &str, total: ByteSize) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!(
+            "lvextend -L {} {}",
+            size_arg(total)?,
+            lv_path_arg(vg, lv)?
+        ))
+    }
+
+    /// # Shrink `VG/LV` to `new_size`
+    /// Data beyond the new size is lost, so without `force` it refuses to touch an open LV,
+    /// e.g. a mounted one, with [CommandRetCode::DataLossRisk] and runs `lvreduce --yes -L <size> VG/LV`.
+    /// Nobody can answer LVM's confirmation prompt under lvm2cmd, the open check stands for it,
+    /// and LVM's other safety checks stay in place, e.g. the filesystem size check on LVM 2.03.17+.
+    /// `force` skips the check and runs `lvreduce --yes --force -L <size> VG/LV`.
+    ///
+    /// Zero size is rejected with [CommandRetCode::InvalidParameters].
+    pub fn lvreduce(
+        vg: &str,
+        lv: &str,
+        new_size: ByteSize,
+        force: bool,
+    ) -> Result<(), CommandRetCode> {
+        let size = size_arg(new_size)?;
+        let lv_arg = lv_path_arg(vg, lv)?;
+        if force {
+            return Lvm::run_quiet(&format!("lvreduce --yes --force -L {size} {lv_arg}"));
+        }
+        let command = format!("lvs {lv_arg}");
+        match lvs_from(&command)?.first() {
+            Some(info) if info.is_open() => Err(CommandRetCode::DataLossRisk),
+            Some(_info) => Lvm::run_quiet(&format!("lvreduce --yes -L {size} {lv_arg}")),
+            None => Err(CommandRetCode::UnexpectedOutput(command)),
+        }
+    }
+
+    /// # Enable zeroing of `VG/LV`
+    /// Runs `lvchange --zero y VG/LV`, which applies to thin pools: blocks are zeroed
+    /// when they're provisioned, so thin LVs never expose stale data.
+    /// It doesn't erase data which is already there.
+    pub fn wipe_lv(vg: &str, lv: &str) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!("lvchange --zero y {}", lv_path_arg(vg, lv)?))
+    }
+
+    /// # Check whether zeroing of `VG/LV` is enabled
+    /// Runs `lvs` for the LV and checks its attributes, see [crate::report::LvInfo::is_zeroed]
+    pub fn lv_is_zeroed(vg: &str, lv: &str) -> Result<bool, CommandRetCode> {
+        let command = format!("lvs {}", lv_path_arg(vg, lv)?);
+        match lvs_from(&command)?.first() {
+            Some(info) => Ok(info.is_zeroed()),
+            None => Err(CommandRetCode::UnexpectedOutput(command)),
+        }
+    }
+
+    /// # Check whether `VG/LV` is active
+    /// Runs `lvs` for the LV and checks its state, see [crate::report::LvInfo::is_active]
+    pub fn lv_is_active(vg: &str, lv: &str) -> Result<bool, CommandRetCode> {
+        let command = format!("lvs {}", lv_path_arg(vg, lv)?);
+        match lvs_from(&command)?.first() {
+            Some(info) => Ok(info.is_active()),
+            None => Err(CommandRetCode::UnexpectedOutput(command)),
+        }
+    }
+}
+
+/// # Active LV guard
+/// Obtained with [Lvm::activate_scoped], deactivates the LV when dropped.
+/// Deactivation errors can't be returned from [Drop], so they are reported to the log sink (see [Lvm::set_log_sink]) or stderr.
+#[derive(Debug)]
+pub struct LvmTransaction {
+    vg: String,
+    lv: String,
+    keep_active: bool,
+}
+
+impl LvmTransaction {
+    /// Don't deactivate the LV on drop if `keep` is true
+    pub fn keep_active(&mut self, keep: bool) {
+        self.keep_active = keep;
+    }
+}
+
+impl Drop for LvmTransaction {
+    fn drop(&mut self) {
+        if self.keep_active {
+            return;
+        }
+        if let Err(e) = Lvm::deactivate_lv(&self.vg, &self.lv) {
+            log_error(
+                file!(),
+                line!(),
+                &format!("failed to deactivate {}/{}: {e}", self.vg, self.lv),
+            );
+        }
+    }
+}
+
+impl LvManager {
+    /// `lvcreate`, see [LvCreateOptions]
+    pub fn lvcreate(&self, options: &LvCreateOptions) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&options.command()?)
+    }
+
+    /// `lvremove [-f] VG/LV`
+    pub fn lvremove(&self, vg: &str, lv: &str, force: bool) -> Result<(), CommandRetCode> {
+        let force = if force { "-f " } else { "" };
+        Lvm::run_quiet(&format!("lvremove {force}{}", lv_path_arg(vg, lv)?))
+    }
+
+    /// `lvresize -L <size> VG/LV`
+    pub fn lvresize(&self, vg: &str, lv: &str, size: ByteSize) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!(
+            "lvresize -L {} {}",
+            size_arg(size)?,
+            lv_path_arg(vg, lv)?
+        ))
+    }
+
+    /// `lvrename VG OLD NEW`
+    pub fn lvrename(&self, vg: &str, old: &str, new: &str) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!(
+            "lvrename {} {} {}",
+            quote(validate_vg_name(vg)?),
+            quote(validate_lv_name(old)?),
+            quote(validate_lv_name(new)?)
+        ))
+    }
+
+    /// `lvchange`, see [LvChangeOptions]
+    pub fn lvchange(
+        &self,
+        vg: &str,
+        lv: &str,
+        options: &LvChangeOptions,
+    ) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&options.command(vg, lv)?)
+    }
+}
+
+/// # Segment types for `lvcreate --type`
+/// See `man 7 lvmraid`, `man 7 lvmthin`, etc
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LvType {
+    Linear,
+    Striped,
+    Snapshot,
+    Mirror,
+    Raid0,
+    Raid1,
+    Raid4,
+    Raid5,
+    Raid6,
+    Raid10,
+    Thin,
+    ThinPool,
+    Cache,
+    CachePool,
+    Vdo,
+    VdoPool,
+    Writecache,
+}
+
+impl Display for LvType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Linear => "linear",
+            Self::Striped => "striped",
+            Self::Snapshot => "snapshot",
+            Self::Mirror => "mirror",
+            Self::Raid0 => "raid0",
+            Self::Raid1 => "raid1",
+            Self::Raid4 => "raid4",
+            Self::Raid5 => "raid5",
+            Self::Raid6 => "raid6",
+            Self::Raid10 => "raid10",
+            Self::Thin => "thin",
+            Self::ThinPool => "thin-pool",
+            Self::Cache => "cache",
+            Self::CachePool => "cache-pool",
+            Self::Vdo => "vdo",
+            Self::VdoPool => "vdo-pool",
+            Self::Writecache => "writecache",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// # Arguments of `lvcreate`
+/// Either size or extents is required, unless it's a snapshot or a thin LV with virtual size.
+///
+/// ```
+/// use lvm_sys2::{lv::LvCreateOptions, units::ByteSize};
+/// let cmd = LvCreateOptions::new("vg0").name("data").size(ByteSize(4096)).command();
+/// assert_eq!(cmd.unwrap(), "lvcreate -L 4096b -n 'data' 'vg0'");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LvCreateOptions {
+    vg: String,
+    name: Option<String>,
+    size: Option<ByteSize>,
+    extents: Option<u64>,
+    thin: bool,
+    snapshot_of: Option<String>,
+    lv_type: Option<LvType>,
+    tags: Vec<String>,
+    chunk_size: Option<ByteSize>,
+    virtual_size: Option<ByteSize>,
+    thin_pool: Option<String>,
+    raid: Option<RaidLvOptions>,
+    pool_metadata_size: Option<ByteSize>,
+    cache_policy: Option<CachePolicy>,
+    vdo: Option<VdoLvOptions>,
+}
+
+impl LvCreateOptions {
+    /// Create LV in `vg`
+    pub fn new(vg: &str) -> Self {
+        Self {
+            vg: vg.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// `-n <name>`
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// `-L <size>`
+    pub fn size(mut self, size: ByteSize) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// `-l <extents>`
+    pub fn extents(mut self, extents: u64) -> Self {
+        self.extents = Some(extents);
+        self
+    }
+
+    /// `-T`
+    pub fn thin(mut self) -> Self {
+        self.thin = true;
+        self
+    }
+
+    /// `-s VG/<origin>` - snapshot of the `origin` LV in the same VG
+    pub fn snapshot(mut self, origin: &str) -> Self {
+        self.snapshot_of = Some(origin.to_string());
+        self
+    }
+
+    /// `--type <type>`
+    pub fn lv_type(mut self, lv_type: LvType) -> Self {
+        self.lv_type = Some(lv_type);
+        self
+    }
+
+    /// `--addtag <tag>`, could be called multiple times
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    /// `-c <chunk size>`
+    pub fn chunk_size(mut self, chunk_size: ByteSize) -> Self {
+        self.chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// `-V <virtual size>` - size of a thin LV
+    pub fn virtual_size(mut self, virtual_size: ByteSize) -> Self {
+        self.virtual_size = Some(virtual_size);
+        self
+    }
+
+    /// `--thinpool <pool>` - thin pool in the same VG to create a thin LV in
+    pub fn thin_pool(mut self, pool: &str) -> Self {
+        self.thin_pool = Some(pool.to_string());
+        self
+    }
+
+    /// `--poolmetadatasize <size>` - metadata LV size of a thin or cache pool
+    pub fn pool_metadata_size(mut self, size: ByteSize) -> Self {
+        self.pool_metadata_size = Some(size);
+        self
+    }
+
+    /// `--cachepolicy <policy>`
+    pub fn cache_policy(mut self, policy: CachePolicy) -> Self {
+        self.cache_policy = Some(policy);
+        self
+    }
+
+    /// VDO pool to create along with the LV, sets the type, size and virtual size, see [VdoLvOptions]
+    pub fn vdo(mut self, vdo: VdoLvOptions) -> Self {
+        self.lv_type = Some(LvType::Vdo);
+        self.size = Some(vdo.pool_size);
+        self.virtual_size = Some(vdo.virtual_size);
+        self.vdo = Some(vdo);
+        self
+    }
+
+    /// RAID layout, see [RaidLvOptions]
+    pub fn raid(mut self, raid: RaidLvOptions) -> Self {
+        self.raid = Some(raid);
+        self
+    }
+
+    /// # Validate options and assemble `lvcreate` command line
+    /// Returns [CommandRetCode::InvalidParameters] for invalid names, tags, zero sizes, size / extents misuse,
+    /// or too few stripes for the RAID level
+    pub fn command(&self) -> Result<String, CommandRetCode> {
+        let mut args = vec!["lvcreate".to_string()];
+        if let Some(lv_type) = self.lv_type {
+            args.push(format!("--type {lv_type}"));
+        }
+        if let Some(raid) = &self.raid {
+            args.extend(raid.args(self.lv_type)?);
+        }
+        match (self.size, self.extents) {
+            (Some(size), None) => args.push(format!("-L {}", size_arg(size)?)),
+            (None, Some(0)) => return Err(CommandRetCode::InvalidParameters),
+            (None, Some(extents)) => args.push(format!("-l {extents}")),
+            // thin snapshots and thin LVs have no size
+            (None, None) if self.snapshot_of.is_some() || self.virtual_size.is_some() => (),
+            _ => return Err(CommandRetCode::InvalidParameters),
+        }
+        if let Some(virtual_size) = self.virtual_size {
+            args.push(format!("-V {}", size_arg(virtual_size)?));
+        }
+        if let Some(chunk_size) = self.chunk_size {
+            args.push(format!("-c {}", size_arg(chunk_size)?));
+        }
+        if let Some(size) = self.pool_metadata_size {
+            args.push(format!("--poolmetadatasize {}", size_arg(size)?));
+        }
+        if let Some(policy) = self.cache_policy {
+            args.push(format!("--cachepolicy {policy}"));
+        }
+        if let Some(vdo) = &self.vdo {
+            args.extend(vdo.args()?);
+        }
+        if let Some(pool) = &self.thin_pool {
+            args.push(format!("--thinpool {}", quote(validate_lv_name(pool)?)));
+        }
+        if let Some(name) = &self.name {
+            args.push(format!("-n {}", quote(validate_lv_name(name)?)));
+        }
+        if self.thin {
+            args.push("-T".to_string());
+        }
+        for tag in &self.tags {
+            args.push(format!("--addtag {}", quote(validate_tag(tag)?)));
+        }
+        match (&self.snapshot_of, &self.vdo) {
+            (Some(origin), _) => args.push(format!("-s {}", lv_path_arg(&self.vg, origin)?)),
+            (None, Some(vdo)) => args.push(lv_path_arg(&self.vg, &vdo.pool_name)?),
+            (None, None) => args.push(quote(validate_vg_name(&self.vg)?)),
+        }
+
+        Ok(args.join(" "))
+    }
+}
+
+/// # RAID layout of [LvCreateOptions::raid]
+/// RAID level itself is set with [LvCreateOptions::lv_type], stripes are checked against it:
+/// raid5 (and raid4) needs at least 2 stripes, raid6 - at least 3.
+///
+/// ```
+/// use lvm_sys2::{lv::{LvCreateOptions, LvType, RaidLvOptions}, units::ByteSize};
+/// let cmd = LvCreateOptions::new("vg0")
+///     .name("data")
+///     .lv_type(LvType::Raid5)
+///     .extents(100)
+///     .raid(RaidLvOptions::new().stripes(3))
+///     .command();
+/// assert_eq!(cmd.unwrap(), "lvcreate --type raid5 -i 3 -l 100 -n 'data' 'vg0'");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RaidLvOptions {
+    mirrors: Option<u32>,
+    stripes: Option<u32>,
+    stripe_size: Option<ByteSize>,
+    region_size: Option<ByteSize>,
+}
+
+impl RaidLvOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `-m <mirrors>` - number of additional copies, e.g. 1 for a 2-way raid1
+    pub fn mirrors(mut self, mirrors: u32) -> Self {
+        self.mirrors = Some(mirrors);
+        self
+    }
+
+    /// `-i <stripes>` - number of data stripes, parity ones aren't counted
+    pub fn stripes(mut self, stripes: u32) -> Self {
+        self.stripes = Some(stripes);
+        self
+    }
+
+    /// `-I <stripe size>`
+    pub fn stripe_size(mut self, stripe_size: ByteSize) -> Self {
+        self.stripe_size = Some(stripe_size);
+        self
+    }
+
+    /// `-R <region size>` - granularity of the resync tracking
+    pub fn region_size(mut self, region_size: ByteSize) -> Self {
+        self.region_size = Some(region_size);
+        self
+    }
+
+    fn args(&self, lv_type: Option<LvType>) -> Result<Vec<String>, CommandRetCode> {
+        let mut args = vec![];
+        if let Some(mirrors) = self.mirrors {
+            args.push(format!("-m {mirrors}"));
+        }
+        if let Some(stripes) = self.stripes {
+            let min_stripes = match lv_type {
+                Some(LvType::Raid4 | LvType::Raid5) => 2,
+                Some(LvType::Raid6) => 3,
+                _ => 1,
+            };
+            if stripes < min_stripes {
+                return Err(CommandRetCode::InvalidParameters);
+            }
+            args.push(format!("-i {stripes}"));
+        }
+        if let Some(stripe_size) = self.stripe_size {
+            args.push(format!("-I {}", size_arg(stripe_size)?));
+        }
+        if let Some(region_size) = self.region_size {
+            args.push(format!("-R {}", size_arg(region_size)?));
+        }
+        Ok(args)
+    }
+}
+
+/// # Arguments of `lvchange`
+/// At least one change is required.
+#[derive(Debug, Clone, Default)]
+pub struct LvChangeOptions {
+    activate: Option<bool>,
+    read_only: Option<bool>,
+    add_tags: Vec<String>,
+    del_tags: Vec<String>,
+}
+
+impl LvChangeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `-ay` / `-an`
+    pub fn activate(mut self, activate: bool) -> Self {
+        self.activate = Some(activate);
+        self
+    }
+
+    /// `-p r` / `-p rw`
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = Some(read_only);
+        self
+    }
+
+    /// `--addtag <tag>`, could be called multiple times
+    pub fn add_tag(mut self, tag: &str) -> Self {
+        self.add_tags.push(tag.to_string());
+        self
+    }
+
+    /// `--deltag <tag>`, could be called multiple times
+    pub fn del_tag(mut self, tag: &str) -> Self {
+        self.del_tags.push(tag.to_string());
+        self
+    }
+
+    /// # Validate options and assemble `lvchange` command line for `VG/LV`
+    pub fn command(&self, vg: &str, lv: &str) -> Result<String, CommandRetCode> {
+        let mut args = vec!["lvchange".to_string()];
+        match self.activate {
+            Some(true) => args.push("-ay".to_string()),
+            Some(false) => args.push("-an".to_string()),
+            None => (),
+        }
+        match self.read_only {
+            Some(true) => args.push("-p r".to_string()),
+            Some(false) => args.push("-p rw".to_string()),
+            None => (),
+        }
+        for tag in &self.add_tags {
+            args.push(format!("--addtag {}", quote(validate_tag(tag)?)));
+        }
+        for tag in &self.del_tags {
+            args.push(format!("--deltag {}", quote(validate_tag(tag)?)));
+        }
+        if args.len() == 1 {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        args.push(lv_path_arg(vg, lv)?);
+
+        Ok(args.join(" "))
+    }
+}
+
+/// # Check LV name against LVM rules
+/// Same as [vg_validate_name], but some prefixes (e.g. `snapshot`) and substrings (e.g. `_tmeta`) are reserved,
+/// see "VALID NAMES" in `man 8 lvm`
+pub fn lv_validate_name(name: &str) -> Result<(), NameValidationError> {
+    vg_validate_name(name)?;
+    let reserved = RESERVED_LV_PREFIXES
+        .iter()
+        .find(|p| name.starts_with(*p))
+        .or_else(|| RESERVED_LV_SUBSTRINGS.iter().find(|s| name.contains(*s)));
+    match reserved {
+        Some(word) => Err(NameValidationError::ReservedWord(word)),
+        None => Ok(()),
+    }
+}
+
+/// LV name if it's valid, see [lv_validate_name]
+pub(crate) fn validate_lv_name(name: &str) -> Result<&str, CommandRetCode> {
+    lv_validate_name(name)?;
+    Ok(name)
+}
+
+/// # Check tag against LVM rules
+/// ASCII alphanumerics and `_`, `+`, `.`, `-` up to 128 characters
+pub(crate) fn validate_tag(tag: &str) -> Result<&str, CommandRetCode> {
+    if tag.is_empty()
+        || tag.len() > MAX_NAME_LEN
+        || !tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '.' | '-'))
+    {
+        return Err(CommandRetCode::InvalidParameters);
+    }
+    Ok(tag)
+}
+
+/// Quoted `VG/LV` argument
+pub(crate) fn lv_path_arg(vg: &str, lv: &str) -> Result<String, CommandRetCode> {
+    Ok(quote(&format!(
+        "{}/{}",
+        validate_vg_name(vg)?,
+        validate_lv_name(lv)?
+    )))
+}
+
+/// Size in bytes, zero is rejected
+pub(crate) fn size_arg(size: ByteSize) -> Result<String, CommandRetCode> {
+    match size {
+        ByteSize(0) => Err(CommandRetCode::InvalidParameters),
+        ByteSize(bytes) => Ok(format!("{bytes}b")),
+    }
+}
+
+/// lvm2cmd treats a token started with a quote as a single argument till the closing quote
+pub(crate) fn quote(arg: &str) -> String {
+    format!("'{arg}'")
+}
@@ -3,12 +3,22 @@
 //! The main interface is [Lvm::run], which runs the specified command and returns output as JSON or error if any.
 
 use std::{
-    ffi::{CStr, CString, NulError, c_char, c_int, c_void},
+    cell::{Cell, RefCell},
+    error::Error,
+    ffi::{CStr, CString, NulError, OsStr, c_char, c_int, c_void},
+    fmt::Display,
+    hash::{Hash, Hasher},
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    process::ExitCode,
     str::FromStr,
     sync::{
-        Condvar, LazyLock, Mutex,
-        mpsc::{self, Receiver, Sender},
+        Arc, Mutex, OnceLock, RwLock,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
     },
+    thread,
+    time::{Duration, Instant, SystemTime},
 };
 
 pub use serde_json::Value;
@@ -18,16 +28,56 @@ use crate::{lvm2_exit, lvm2_init, lvm2_log_fn, lvm2_run};
 // addition to every command issued
 const DEFAULT_LVM_FLAGS: &str = "--reportformat json";
 
+/// LVM prints it before anything else with `--test`
+const TEST_MODE_MESSAGE: &str =
+    "TEST MODE: Metadata will NOT be updated and volumes will not be (de)activated.";
+
+/// Parts of LVM error messages telling the object a command creates is already there, see [Lvm::run_idempotent]
+const ALREADY_EXISTS_PATTERNS: &[&str] = &["already exists", "is already a physical volume"];
+
+/// output of a successful command which printed nothing
+pub(crate) const NO_OUTPUT: &str = r#"{"rust_logger": "no messages from command"}"#;
+
+// user-defined addition to every command issued, see Lvm::set_default_flags
+static EXTRA_LVM_FLAGS: RwLock<String> = RwLock::new(String::new());
+
 /// Singletone to sync calls to LVM. Experiments showed that Lvm::new() may obtain the same handler leading to double-free, access-after-free, etc
-static LVM: LazyLock<Mutex<Result<Lvm, CommandRetCode>>> = LazyLock::new(|| Mutex::new(Lvm::new()));
+/// It's initialized on first access, None after [Lvm::reset]
+static LVM: Mutex<Option<Result<Lvm, CommandRetCode>>> = Mutex::new(None);
+
+thread_local! {
+    // Output of commands being run on this thread, the innermost call is the last.
+    // lvm2cmd calls the log function synchronously from lvm2_run, so each call collects its own output
+    // even if the log function (e.g. a log sink) runs another command.
+    static CAPTURED_CMD_DATA: RefCell<Vec<Captured>> = const { RefCell::new(Vec::new()) };
+    // FATAL / ERROR messages of the last command run on this thread, see Lvm::last_error_message
+    static LAST_ERROR_MESSAGE: RefCell<String> = const { RefCell::new(String::new()) };
+    // what lvm2_run returned for the last command run on this thread, see Lvm::run_with_retcode
+    static LAST_RET_CODE: Cell<i32> = const { Cell::new(0) };
+    // whether Lvm::with_thread_local is running on this thread
+    #[cfg(feature = "unsafe-parallel")]
+    static IN_THREAD_LOCAL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// What a single command logged
+#[derive(Default)]
+struct Captured {
+    /// PRINT messages except warnings, concatenated
+    output: String,
+    /// whether a line break follows every PRINT message, LVM prints report rows of non-JSON formats without it
+    line_breaks: bool,
+    /// the rest of messages, see [is_warning]
+    logs: Vec<LogMessage>,
+}
+
+/// User-provided receiver of all LVM logs, see [Lvm::set_log_sink]
+static LOG_SINK: RwLock<Option<Arc<dyn LvmLogSink + Send + Sync>>> = RwLock::new(None);
+
+/// Whether [log_capturer] is registered as lvm2cmd's log function, see [Lvm::use_default_log_fn]
+static INTERNAL_LOG_FN: AtomicBool = AtomicBool::new(true);
 
-// Channel to get data from the logs
-// LVM waits for the data on mutex + condvar
-// log recorder searches for commands and pushes them to the channel
-static CHANNEL: LazyLock<Mutex<(Sender<String>, Receiver<String>)>> =
-    LazyLock::new(|| Mutex::new(mpsc::channel()));
-static DATA_ARRIVED: Condvar = Condvar::new();
-static CAPTURED_CMD_DATA: Mutex<String> = Mutex::new(String::new()); // whether special log line arrived that command is executing
+/// Whether commands run with udev sync, see [Lvm::set_udev_sync]
+static UDEV_SYNC: OnceLock<bool> = OnceLock::new();
 
 /// LVM handle keeper
 pub struct Lvm {
@@ -57,7 +107,357 @@ impl Lvm {
     pub fn run(
         command: &str,
     ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
-        Self::acquire_and(|lvm| lvm._run(format!("{command} {DEFAULT_LVM_FLAGS}")))
+        Self::parse(Self::dispatch(command, Self::command_line(command)).0?)
+    }
+
+    /// # Run LVM report command with only the specified fields
+    /// Same as [Lvm::run] with `-o <fields>`, which makes the output smaller.
+    /// See `<command> -o help` for available fields.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if no fields are given or a field isn't alphanumeric + `_`.
+    pub fn run_fields(
+        command: &str,
+        fields: &[&str],
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        if fields.is_empty()
+            || !fields
+                .iter()
+                .all(|f| !f.is_empty() && f.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        Self::run(&format!("{command} -o {}", fields.join(",")))
+    }
+
+    /// # Run LVM command and get the raw return code of `lvm2_run`
+    /// Same as [Lvm::run], along with the code as lvm2cmd returned it, e.g. for bug reports
+    /// or to recognize codes of newer LVM versions, which are [CommandRetCode::Unknown] otherwise.
+    ///
+    /// The code is 0 if `lvm2_run` wasn't called, e.g. LVM failed to init or the command line contains NUL.
+    /// Under [crate::mock::MockLvm] it's the [i32] of the response.
+    pub fn run_with_retcode(
+        command: &str,
+    ) -> (
+        Result<serde_json::Map<String, serde_json::Value>, CommandRetCode>,
+        i32,
+    ) {
+        LAST_RET_CODE.set(0);
+        let res = Self::run(command);
+        (res, LAST_RET_CODE.get())
+    }
+
+    /// # Run LVM command and extract a single value
+    /// Same as [Lvm::run], but returns the value at the [JSON pointer](https://www.rfc-editor.org/rfc/rfc6901),
+    /// e.g. `/report/0/vg/0/vg_free` for free space of the first VG. Handy for scripts.
+    ///
+    /// Returns [CommandRetCode::JsonPointerNotFound] if the pointer doesn't match anything,
+    /// including pointers not starting with `/`.
+    pub fn run_json_pointer(command: &str, pointer: &str) -> Result<Value, CommandRetCode> {
+        let mut output = Value::Object(Self::run(command)?);
+        output
+            .pointer_mut(pointer)
+            .map(Value::take)
+            .ok_or_else(|| CommandRetCode::JsonPointerNotFound(pointer.to_string()))
+    }
+
+    /// # Run LVM report command sorted by the specified fields
+    /// Same as [Lvm::run] with `--sort <fields>`, LVM sorts the rows, e.g. for stable pagination.
+    /// A field prefixed with `-` sorts in descending order, e.g. `&["vg_name", "-lv_size"]`.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if no fields are given or a field isn't lowercase + `_`.
+    pub fn run_sorted(
+        command: &str,
+        sort_fields: &[&str],
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        if sort_fields.is_empty()
+            || !sort_fields.iter().all(|f| {
+                let name = f.strip_prefix('-').unwrap_or(f);
+                !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+            })
+        {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        Self::run(&format!("{command} --sort {}", sort_fields.join(",")))
+    }
+
+    /// # Run several LVM commands under a single lock of the global singleton
+    /// Commands run in order, results are captured independently - a failed command doesn't stop the rest.
+    /// Useful to get a consistent view, e.g. `pvs` + `vgs` + `lvs`, as no other command could sneak in between.
+    ///
+    /// If the singleton couldn't be acquired, each result contains the error.
+    pub fn run_multiple(
+        commands: &[&str],
+    ) -> Vec<Result<serde_json::Map<String, serde_json::Value>, CommandRetCode>> {
+        #[cfg(feature = "test-utils")]
+        if crate::mock::is_active() {
+            return commands.iter().map(|command| Self::run(command)).collect();
+        }
+        Self::acquire_and(|lvm| {
+            Ok(commands
+                .iter()
+                .map(|command| lvm._run(Self::command_line(command)))
+                .collect())
+        })
+        .unwrap_or_else(|e| vec![Err(e); commands.len()])
+    }
+
+    /// # Async version of [Lvm::run]
+    /// Runs the command on tokio's blocking thread pool, so it doesn't stall the async runtime.
+    /// Commands are still serialized by the global singleton.
+    ///
+    /// All errors are the same as for [Lvm::run].
+    /// A panic in the blocking thread results into [CommandRetCode::GlobalStatePoisoned].
+    #[cfg(feature = "tokio")]
+    pub async fn run_async(
+        command: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        let command = command.to_string();
+        tokio::task::spawn_blocking(move || Self::run(&command))
+            .await
+            .map_err(|_e| CommandRetCode::GlobalStatePoisoned)?
+    }
+
+    /// # Run LVM command with a deadline
+    /// Same as [Lvm::run], but gives up waiting after `timeout` and returns [CommandRetCode::Timeout].
+    ///
+    /// lvm2cmd calls can't be cancelled, so the command keeps running on a detached thread till it finishes on its own.
+    /// It holds the global singleton meanwhile, so the next command waits for it to complete.
+    pub fn run_with_timeout(
+        command: &str,
+        timeout: Duration,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        let (tx, rx) = mpsc::channel();
+        let command = command.to_string();
+        thread::spawn(move || {
+            // receiver is gone if the caller timed out - the result is dropped then
+            let _ = tx.send(Self::run(&command));
+        });
+        match rx.recv_timeout(timeout) {
+            Ok(res) => res,
+            Err(RecvTimeoutError::Timeout) => Err(CommandRetCode::Timeout),
+            // the thread panicked before sending the result
+            Err(RecvTimeoutError::Disconnected) => Err(CommandRetCode::GlobalStatePoisoned),
+        }
+    }
+
+    /// # Run LVM command with extra environment variables
+    /// Same as [Lvm::run], but sets `env` for the duration of the call, e.g. `LVM_SYSTEM_DIR`, `LVM_CONF` or `DM_DEV_DIR`.
+    /// Previous values are restored afterwards, variables which weren't set are removed.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if a name is empty or contains `=`, or anything contains NUL.
+    ///
+    /// # Safety
+    /// Environment is process-wide, so it's the same as for [std::env::set_var]:
+    /// no other thread may read or write the environment during the call.
+    /// Use it in single-threaded programs or tests only.
+    pub unsafe fn run_with_env(
+        command: &str,
+        env: &[(&str, &str)],
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        if env.iter().any(|(name, value)| {
+            name.is_empty() || name.contains(['=', '\0']) || value.contains('\0')
+        }) {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        #[cfg(feature = "test-utils")]
+        if let Some(res) = crate::mock::response(command) {
+            return Self::parse(res?);
+        }
+        let env: Vec<_> = env
+            .iter()
+            .map(|(name, value)| (*name, OsStr::new(value)))
+            .collect();
+        Self::acquire_and(|lvm| {
+            // SAFETY: it's on the caller, see above
+            unsafe { with_env(&env, || lvm._run(Self::command_line(command))) }
+        })
+    }
+
+    /// # Run LVM command and return its output as is
+    /// Same as [Lvm::run], but skips JSON deserialization.
+    /// Useful to forward the output somewhere else without re-serializing it.
+    ///
+    /// # Example
+    /// ```
+    /// use lvm_sys2::lvm::Lvm;
+    /// let result = Lvm::run_raw("lvs");
+    /// assert!(result.is_ok());
+    /// ```
+    pub fn run_raw(command: &str) -> Result<String, CommandRetCode> {
+        Self::dispatch(command, Self::command_line(command)).0
+    }
+
+    /// # Run LVM report command in another output format
+    /// [ReportFormat::Json] is the same as [Lvm::run_raw]. Other formats replace `--reportformat json`,
+    /// flags of [Lvm::set_default_flags] still apply. Useful to feed the output directly into another tool.
+    ///
+    /// The output is returned as is, a line per report row (and heading), it's empty if the command printed nothing.
+    pub fn run_format(command: &str, format: ReportFormat) -> Result<String, CommandRetCode> {
+        if format == ReportFormat::Json {
+            return Self::run_raw(command);
+        }
+        let output = Self::dispatch(command, Self::command_line_with(command, format.flags())).0?;
+        // the placeholder is only meaningful for JSON
+        Ok(if output == NO_OUTPUT {
+            String::new()
+        } else {
+            output
+        })
+    }
+
+    /// # Run LVM command which produces no report
+    /// Runs the command as is, without `--reportformat json` and flags of [Lvm::set_default_flags],
+    /// and only checks the return code. Write commands, e.g. `pvcreate` or `lvremove`, print plain text messages,
+    /// which aren't worth parsing.
+    pub fn run_quiet(command: &str) -> Result<(), CommandRetCode> {
+        Self::run_without_flags(command).map(|_output| ())
+    }
+
+    /// # Run LVM command in read-only mode
+    /// Same as [Lvm::run] with `--readonly`: LVM doesn't write metadata and doesn't take VG locks,
+    /// so it's safer to query a VG another host holds exclusively. Write commands fail in this mode.
+    ///
+    /// Mixing [Lvm::run] and [Lvm::run_readonly] on the same VG concurrently is undefined behavior
+    /// with respect to LVM locking: a read-only query may see metadata in the middle of an update.
+    pub fn run_readonly(
+        command: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        Self::run(&format!("{command} --readonly"))
+    }
+
+    /// # Run LVM command without changing anything
+    /// Same as [Lvm::run] with `--test`: LVM parses and validates the command, but doesn't write metadata
+    /// or (de)activate volumes. Useful in CI to check command lines without real block devices.
+    ///
+    /// Commands often print nothing in test mode, which results into an empty map.
+    /// Plain text messages of write commands, e.g. `Logical volume "lv0" created.`, come as `{"rust_logger": "<messages>"}`.
+    pub fn run_in_test_mode(
+        command: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        let output = Self::run_raw(&format!("{command} --test"))?;
+        // the banner comes as PRINT, so it's glued to the rest of the output
+        let output = output.replacen(TEST_MODE_MESSAGE, "", 1);
+        let output = output.trim();
+        if output.is_empty() || output == NO_OUTPUT {
+            return Ok(serde_json::Map::new());
+        }
+        match serde_json::from_str(output) {
+            Ok(report) => Ok(report),
+            Err(_e) => Ok(serde_json::Map::from_iter([(
+                "rust_logger".to_string(),
+                Value::String(output.to_string()),
+            )])),
+        }
+    }
+
+    /// # Set flags to add to every command
+    /// They go along with `--reportformat json`, which is always there, e.g. `--units b --nosuffix`
+    /// to get sizes in bytes. The flags replace previously set ones, empty string resets them.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if the flags contain `--reportformat` - LVM fails on duplicates.
+    pub fn set_default_flags(flags: &str) -> Result<(), CommandRetCode> {
+        if flags.contains("--reportformat") {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        *EXTRA_LVM_FLAGS
+            .write()
+            .map_err(|_e| CommandRetCode::GlobalStatePoisoned)? = flags.trim().to_string();
+        Ok(())
+    }
+
+    /// # Configure udev synchronization for slow udev environments
+    /// In containers and CI udev may never confirm device changes, so LVM commands wait for it and fail.
+    /// Sets `LVM_SUPPRESS_FD_WARNINGS=1` and makes every command run with `--config activation/udev_sync=<0|1>`.
+    /// Commands which pass their own `--config` don't get it, except [Lvm::run_with_config], which merges it in.
+    /// LVM has no setting for how long to wait for udev, use [Lvm::run_with_timeout] to bound a command.
+    ///
+    /// It can be called before the first command. Returns [CommandRetCode::AlreadyInitialized] if it's already configured,
+    /// including by [Lvm::disable_udev_sync].
+    ///
+    /// # Safety
+    /// Same as for [std::env::set_var]: no other thread may read or write the environment during the call.
+    pub unsafe fn set_udev_sync(enabled: bool) -> Result<(), CommandRetCode> {
+        UDEV_SYNC
+            .set(enabled)
+            .map_err(|_enabled| CommandRetCode::AlreadyInitialized)?;
+        // SAFETY: it's on the caller, see above
+        unsafe { std::env::set_var("LVM_SUPPRESS_FD_WARNINGS", "1") };
+        Ok(())
+    }
+
+    /// # Disable udev synchronization
+    /// Same as [Lvm::set_udev_sync] with `false`
+    ///
+    /// # Safety
+    /// Same as for [Lvm::set_udev_sync]
+    pub unsafe fn disable_udev_sync() -> Result<(), CommandRetCode> {
+        // SAFETY: it's on the caller, see above
+        unsafe { Self::set_udev_sync(false) }
+    }
+
+    /// # Whether commands run with udev sync
+    /// What [Lvm::set_udev_sync] set, `None` if it's not configured, so LVM follows `lvm.conf`
+    pub fn udev_sync() -> Option<bool> {
+        UDEV_SYNC.get().copied()
+    }
+
+    /// Setting of [Lvm::set_udev_sync] for `--config`, if it's configured
+    pub(crate) fn udev_sync_config() -> Option<&'static str> {
+        UDEV_SYNC.get().map(|enabled| match enabled {
+            true => "activation/udev_sync=1",
+            false => "activation/udev_sync=0",
+        })
+    }
+
+    /// Add `--config` of [Lvm::set_udev_sync] unless the line already has one, LVM rejects repeated `--config`
+    fn with_udev_sync(mut line: String) -> String {
+        if let Some(config) = Self::udev_sync_config()
+            && !line.contains("--config")
+        {
+            line.push_str(" --config ");
+            line.push_str(config);
+        }
+        line
+    }
+
+    /// run command as is and return the captured output, for commands which don't support the default flags
+    pub(crate) fn run_without_flags(command: &str) -> Result<String, CommandRetCode> {
+        Self::dispatch(command, Self::with_udev_sync(command.to_string())).0
+    }
+
+    /// command with all the default flags
+    fn command_line(command: &str) -> String {
+        Self::command_line_with(command, DEFAULT_LVM_FLAGS)
+    }
+
+    /// command with the format flags instead of `--reportformat json` and the extra flags
+    fn command_line_with(command: &str, format_flags: &str) -> String {
+        let extra = EXTRA_LVM_FLAGS.read().unwrap_or_else(|e| e.into_inner());
+        Self::with_udev_sync(if extra.is_empty() {
+            format!("{command} {format_flags}")
+        } else {
+            format!("{command} {format_flags} {extra}")
+        })
+    }
+
+    /// run the command line under the global singleton, `command` is what [crate::mock::MockLvm] responds to
+    #[cfg_attr(not(feature = "test-utils"), allow(unused_variables))]
+    fn dispatch(command: &str, line: String) -> (Result<String, CommandRetCode>, Vec<LogMessage>) {
+        #[cfg(feature = "test-utils")]
+        if let Some(res) = crate::mock::response(command) {
+            LAST_RET_CODE.set(match &res {
+                Ok(_output) => i32::from(CommandRetCode::CommandSucceeded),
+                Err(e) => i32::from(e.clone()),
+            });
+            return (res, vec![]);
+        }
+        Self::acquire_and(|lvm| Ok(lvm._run_logged(line))).unwrap_or_else(|e| (Err(e), vec![]))
+    }
+
+    /// deserialize captured output
+    fn parse(data: String) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        serde_json::from_str(&data)
+            .map_err(|e| CommandRetCode::JsonDeserializationFailed((e, data)))
     }
 
     /// internal command runner
@@ -65,40 +465,294 @@ impl Lvm {
         &mut self,
         command: String,
     ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
-        let cmd = CString::from_str(command.as_str())
-            .map_err(|e| CommandRetCode::InvalidCommandLine(e))?;
-        match CommandRetCode::from(unsafe {
-            lvm2_run(self.handle.as_mut(), cmd.as_c_str().as_ptr())
-        }) {
-            CommandRetCode::CommandSucceeded => (),
-            other => return Err(other),
+        Self::parse(self._run_logged(command).0?)
+    }
+
+    /// internal command runner, returns data captured from logs along with non-PRINT messages
+    fn _run_logged(
+        &mut self,
+        command: String,
+    ) -> (Result<String, CommandRetCode>, Vec<LogMessage>) {
+        let cmd = match CString::from_str(command.as_str()) {
+            Ok(cmd) => cmd,
+            Err(e) => return (Err(CommandRetCode::InvalidCommandLine(e)), vec![]),
+        };
+        // output is only captured by the crate's log function
+        assert!(
+            INTERNAL_LOG_FN.load(Ordering::SeqCst),
+            "lvm2cmd log function isn't the crate's one, see Lvm::use_internal_log_fn"
+        );
+        let line_breaks = !command.contains(DEFAULT_LVM_FLAGS);
+        CAPTURED_CMD_DATA.with_borrow_mut(|stack| {
+            stack.push(Captured {
+                line_breaks,
+                ..Captured::default()
+            })
+        });
+        let (started, timer) = (SystemTime::now(), Instant::now());
+        let raw = unsafe { lvm2_run(self.handle.as_mut(), cmd.as_c_str().as_ptr()) };
+        crate::audit::record(&command, raw, started, timer.elapsed());
+        LAST_RET_CODE.set(raw);
+        let ret = CommandRetCode::from(raw);
+        // pop even on failure, so the output doesn't leak into the next command
+        let Captured { output, logs, .. } = CAPTURED_CMD_DATA
+            .with_borrow_mut(|stack| stack.pop())
+            .unwrap_or_default();
+        LAST_ERROR_MESSAGE.set(error_messages(&logs).join("\n"));
+        let res = match ret {
+            CommandRetCode::CommandSucceeded if output.is_empty() => {
+                // no PRINT messages
+                Ok(NO_OUTPUT.to_string())
+            }
+            CommandRetCode::CommandSucceeded => Ok(output),
+            other => Err(other),
+        };
+        (res, logs)
+    }
+
+    /// Same as [Lvm::run], but errors come along with FATAL / ERROR messages LVM logged for the command
+    pub(crate) fn run_with_errors(
+        command: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, (CommandRetCode, Vec<String>)> {
+        match Self::dispatch(command, Self::command_line(command)) {
+            (Ok(data), _) => Self::parse(data).map_err(|e| (e, vec![])),
+            (Err(e), logs) => Err((e, error_messages(&logs))),
+        }
+    }
+
+    /// Same as [Lvm::run_quiet], but errors come along with FATAL / ERROR messages LVM logged for the command
+    pub(crate) fn run_quiet_with_errors(
+        command: &str,
+    ) -> Result<(), (CommandRetCode, Vec<String>)> {
+        match Self::dispatch(command, Self::with_udev_sync(command.to_string())) {
+            (Ok(_output), _) => Ok(()),
+            (Err(e), logs) => Err((e, error_messages(&logs))),
         }
-        // receive data from logs
-        let ch = CHANNEL
+    }
+
+    /// # Run LVM command which may have been applied already
+    /// Same as [Lvm::run], but [CommandRetCode::ProcessingFailed] is an empty map if LVM says
+    /// the object already exists, e.g. `A volume group called vg0 already exists.` on repeated `vgcreate`
+    /// or `/dev/sdb is already a physical volume` on repeated `pvcreate`.
+    /// Handy for provisioning tools which apply the same configuration over and over.
+    ///
+    /// The existing object isn't checked to match the command, e.g. an LV of another size is fine.
+    pub fn run_idempotent(
+        command: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        match Self::run_with_errors(command) {
+            Err((CommandRetCode::ProcessingFailed, messages)) if is_already_existing(&messages) => {
+                Ok(serde_json::Map::new())
+            }
+            res => res.map_err(|(code, _messages)| code),
+        }
+    }
+
+    /// # Run LVM command and get its log messages along with the output
+    /// Same as [Lvm::run], but also returns all non-PRINT messages the command logged and warnings,
+    /// e.g. `WARNING: Reducing active logical volume to ...`. LVM logs warnings at PRINT level,
+    /// so they come with [LogLevel::PRINT]; they're never a part of the output.
+    /// Which verbose / debug messages are there depends on LVM's logging configuration.
+    pub fn run_collecting_logs(
+        command: &str,
+    ) -> Result<(serde_json::Map<String, serde_json::Value>, Vec<LogMessage>), CommandRetCode> {
+        let (data, logs) = Self::dispatch(command, Self::command_line(command));
+        Ok((Self::parse(data?)?, logs))
+    }
+
+    /// # Run LVM command with more verbose logging
+    /// Same as [Lvm::run] with `-v`, `-vv` or `-vvv`, see [VerbosityLevel].
+    /// Also returns the messages LVM logged at VERBOSE, VERY_VERBOSE and DEBUG levels, one per entry,
+    /// e.g. for debugging what the command actually did.
+    pub fn run_with_verbosity(
+        command: &str,
+        level: VerbosityLevel,
+    ) -> Result<(serde_json::Map<String, serde_json::Value>, Vec<String>), CommandRetCode> {
+        let command = match level.flag() {
+            Some(flag) => format!("{command} {flag}"),
+            None => command.to_string(),
+        };
+        let (output, logs) = Self::run_collecting_logs(&command)?;
+        let verbose = logs
+            .into_iter()
+            .filter(|log| {
+                matches!(
+                    log.level,
+                    LogLevel::VERBOSE | LogLevel::VERY_VERBOSE | LogLevel::DEBUG
+                )
+            })
+            .map(|log| log.message)
+            .collect();
+        Ok((output, verbose))
+    }
+
+    /// # Why the last command failed
+    /// Takes FATAL / ERROR messages the last command run on this thread logged, one per line,
+    /// e.g. `Volume group "vg0" not found`. Each command replaces them, so it's `None` after a successful one
+    /// or if they're already taken. Commands of [Lvm::run_with_timeout] and [Lvm::run_async] run on other threads.
+    ///
+    /// It's a lightweight alternative to a log sink, see [Lvm::set_log_sink] and [Lvm::run_checked].
+    pub fn last_error_message() -> Option<String> {
+        Some(LAST_ERROR_MESSAGE.take()).filter(|message| !message.is_empty())
+    }
+
+    /// # Register a receiver for LVM logs
+    /// LVM logs every message through a single callback, the crate only picks command output from it.
+    /// The sink gets all the messages, including ones consumed by the crate.
+    /// Calling it again replaces the previous sink.
+    /// A panic of the sink is caught and reported to stderr, the message is dropped and the command goes on.
+    pub fn set_log_sink(sink: Arc<dyn LvmLogSink + Send + Sync>) {
+        // the lock is only held to swap the sink, so it can't be poisoned by a panicking one
+        *LOG_SINK.write().unwrap_or_else(|e| e.into_inner()) = Some(sink);
+    }
+
+    /// # Restore lvm2cmd's own log function
+    /// Calls `lvm2_log_fn(NULL)`, so LVM logs the usual way (e.g. to stdout / stderr) for raw lvm2cmd calls
+    /// made outside of the crate, e.g. by a plugin sharing the process.
+    /// The crate can't capture command output meanwhile, so running commands via [Lvm] panics
+    /// (reported as [CommandRetCode::ClosurePanicked]) until [Lvm::use_internal_log_fn] is called.
+    ///
+    /// # Safety
+    /// The log function is process-wide, no lvm2cmd calls may be in progress on other threads.
+    pub unsafe fn use_default_log_fn() {
+        // the lock keeps commands of the crate from running meanwhile
+        let _guard = LVM.lock().unwrap_or_else(|e| e.into_inner());
+        // SAFETY: it's on the caller, see above
+        unsafe { lvm2_log_fn(None) };
+        INTERNAL_LOG_FN.store(false, Ordering::SeqCst);
+    }
+
+    /// # Register the crate's log function
+    /// Reverts [Lvm::use_default_log_fn], e.g. after own lvm2cmd calls re-registered another one.
+    /// [Lvm] does it on init, so it's only needed after switching.
+    ///
+    /// # Safety
+    /// Same as for [Lvm::use_default_log_fn]
+    pub unsafe fn use_internal_log_fn() {
+        let _guard = LVM.lock().unwrap_or_else(|e| e.into_inner());
+        // SAFETY: it's on the caller, see above
+        unsafe { lvm2_log_fn(Some(log_capturer)) };
+        INTERNAL_LOG_FN.store(true, Ordering::SeqCst);
+    }
+
+    /// # Initialize LVM with the options
+    /// Sets environment variables of [LvmInitOptions] for the duration of `lvm2_init`, which reads them.
+    /// Previous values are restored afterwards. Without this call, the global singleton is initialized
+    /// with the process' environment on first use.
+    ///
+    /// Returns
+    /// - [CommandRetCode::AlreadyInitialized] if the singleton is already initialized (or failed to), see [Lvm::reset]
+    /// - [CommandRetCode::InitFailed] if `lvm2_init` failed, the same way as lazy init does
+    ///
+    /// # Safety
+    /// Same as for [Lvm::run_with_env]: no other thread may read or write the environment during the call.
+    pub unsafe fn init_with(options: LvmInitOptions) -> Result<(), CommandRetCode> {
+        let env = options.env()?;
+        let mut guard = LVM
             .lock()
             .map_err(|_e| CommandRetCode::GlobalStatePoisoned)?;
-        let string_data = match ch.1.try_recv() {
-            Ok(res) => res,
-            Err(_) => match DATA_ARRIVED.wait(ch) {
-                Ok(ch) => ch.1.recv().unwrap(), // UNWRAP: the other side cannot be closed - SENDER is static
-                Err(_) => return Err(CommandRetCode::GlobalStatePoisoned),
-            },
-        };
+        if guard.is_some() {
+            return Err(CommandRetCode::AlreadyInitialized);
+        }
+        // SAFETY: it's on the caller, see above
+        let lvm = unsafe { with_env(&env, Lvm::new) };
+        let res = lvm
+            .as_ref()
+            .map(|_lvm| ())
+            .map_err(|_e| CommandRetCode::InitFailed);
+        *guard = Some(lvm);
+        res
+    }
+
+    /// # Run `f` with an own LVM handle of this thread
+    /// Initializes a separate handle with `lvm2_init`, which bypasses the global singleton, passes it to `f`
+    /// and tears it down with `lvm2_exit` afterwards. Threads can run queries concurrently this way,
+    /// e.g. `lvs` of different VGs, with [Lvm::run_local].
+    ///
+    /// lvm2cmd isn't documented to be thread-safe: it has process-wide state, e.g. the log function and config,
+    /// and the reason for the singleton is that concurrent handles were observed to share memory, leading to double-free.
+    ///
+    /// Returns [CommandRetCode::InitFailed] if `lvm2_init` failed
+    /// and [CommandRetCode::AlreadyInitialized] if it's called from `f`.
+    ///
+    /// # Safety
+    /// Nothing here prevents that double-free, it's on the caller:
+    /// - only read-only queries are run with the handle, never write commands
+    /// - the global singleton isn't used (e.g. by [Lvm::run]) while any thread-local handle is alive
+    /// - the LVM version in use was validated to work with concurrent handles, see [Lvm::version]
+    #[cfg(feature = "unsafe-parallel")]
+    pub unsafe fn with_thread_local<T, F: FnOnce(&mut Lvm) -> T>(
+        f: F,
+    ) -> Result<T, CommandRetCode> {
+        if IN_THREAD_LOCAL.replace(true) {
+            return Err(CommandRetCode::AlreadyInitialized);
+        }
+        // reset the flag even if `f` panics
+        struct Reset;
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                IN_THREAD_LOCAL.set(false);
+            }
+        }
+        let _reset = Reset;
+        let mut lvm = Lvm::new()?;
+        Ok(f(&mut lvm))
+    }
+
+    /// # Run LVM command with this handle
+    /// Same as [Lvm::run], but on the handle of [Lvm::with_thread_local] instead of the global singleton.
+    /// [crate::mock::MockLvm] doesn't apply.
+    #[cfg(feature = "unsafe-parallel")]
+    pub fn run_local(
+        &mut self,
+        command: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        self._run(Self::command_line(command))
+    }
 
-        serde_json::from_str(&string_data)
-            .map_err(|e| CommandRetCode::JsonDeserializationFailed((e, string_data)))
+    /// # Check whether LVM could be used
+    /// Initializes the global singleton if it's not yet and returns whether it's usable,
+    /// i.e. `lvm2_init` succeeded and the singleton isn't poisoned.
+    /// Handy for feature detection at startup or to skip tests in environments without LVM.
+    pub fn is_available() -> bool {
+        Self::acquire_and(|_lvm| Ok(())).is_ok()
+    }
+
+    /// # Call raw lvm2cmd bindings with the global LVM handle
+    /// Locks the global singleton and passes its handle to `f`, e.g. to call [crate::lvm2_run] directly.
+    /// Errors are the same as for [Lvm::acquire_and].
+    ///
+    /// # Safety
+    /// The handle must not be used after `f` returns or passed to [crate::lvm2_exit].
+    /// Output of commands run with the handle isn't captured, it only goes to the log sink.
+    pub unsafe fn with_handle<T, F: FnOnce(*mut c_void) -> T>(f: F) -> Result<T, CommandRetCode> {
+        Self::acquire_and(|lvm| Ok(f(lvm.handle.as_mut() as *mut c_void)))
+    }
+
+    /// # Tear down the global LVM handle
+    /// Drops the singleton along with the cached [CommandRetCode::InitFailed], if any,
+    /// so the next call re-initializes LVM, e.g. after the environment was fixed.
+    /// It also recovers from [CommandRetCode::GlobalStatePoisoned].
+    ///
+    /// # Safety
+    /// Calls [crate::lvm2_exit] on the existing handle, so no pointers obtained via [Lvm::with_handle] may be used afterwards.
+    /// LVM's process-wide state is re-initialized, which may break concurrent users of lvm2cmd outside of this crate.
+    pub unsafe fn reset() {
+        let mut guard = LVM.lock().unwrap_or_else(|e| e.into_inner());
+        *guard = None;
+        LVM.clear_poison();
     }
 
     /// # Do NOT use, see [Lvm::run] instead
     /// # Acquire global LVM singleton and run the specified function
     /// It's a building block to run commands. Lazy init happens here and all relevant errors handling
     /// # Panics
-    /// 1. same as Mutex::lock()
-    /// 2. if closure panics
+    /// Same as Mutex::lock(), e.g. if the closure calls it again
     /// # Error
-    /// There are 3 cases when this function could return error:
-    /// - CommandRetCode::InitFailed          - Lvm lazy init failed on first access
+    /// There are 4 cases when this function could return error:
+    /// - CommandRetCode::InitFailed          - Lvm lazy init failed on first access, it's cached until [Lvm::reset]
     /// - CommandRetCode::GlobalStatePoisoned - Mutex holding the global Lvm handler is poisoned (another thread panicked holding the lock => within this function)
+    /// - CommandRetCode::ClosurePanicked     - the closure panicked, the panic is caught so the lock stays usable for others
     /// - other CommandRetCode - inner function returned Err(CommandRetCode)
     ///
     /// Inner function isn't supposed to return CommandRetCode directly.
@@ -110,8 +764,11 @@ impl Lvm {
             Err(_e) => return Err(CommandRetCode::GlobalStatePoisoned),
         };
 
-        match guard.as_mut() {
-            Ok(lvm) => f(lvm),
+        match guard.get_or_insert_with(Lvm::new) {
+            // Lvm only holds the handle, a panic can't leave it half-updated
+            Ok(lvm) => panic::catch_unwind(AssertUnwindSafe(|| f(lvm))).unwrap_or_else(|payload| {
+                Err(CommandRetCode::ClosurePanicked(panic_message(payload)))
+            }),
             Err(_e) => Err(CommandRetCode::InitFailed), // hardocde to avoid ambiguety
         }
     }
@@ -121,7 +778,9 @@ impl Lvm {
         let handle = unsafe { lvm2_init() };
         // SAFETY: LVM subsystem allocates the structure
         unsafe {
-            lvm2_log_fn(Some(log_capturer));
+            if INTERNAL_LOG_FN.load(Ordering::SeqCst) {
+                lvm2_log_fn(Some(log_capturer));
+            }
             handle
                 .as_mut() // check for NULL, peeked here: https://gitlab.com/lvmteam/lvm2/-/blob/main/tools/lvmcmdlib.c#L34
                 .map(|_| Self {
@@ -141,7 +800,10 @@ impl Drop for Lvm {
 }
 
 /// # Possible commands return codes
-/// Contains both - native LVM codes and introduced by the wrapper
+/// Contains both - native LVM codes and introduced by the wrapper.
+///
+/// [serde_json::Error] is neither [Clone] nor [PartialEq], so [CommandRetCode::JsonDeserializationFailed]
+/// compares by error category and the original string, and its clone carries the same category, but may lose the position.
 #[derive(Debug)]
 pub enum CommandRetCode {
     // from lvm2cmd.h
@@ -158,11 +820,40 @@ pub enum CommandRetCode {
     InvalidCommandLine(NulError),
     /// Global object is poisoned - some thread panic-ed on Lvm execution
     GlobalStatePoisoned,
-    /// Channel to get data from logs is poisoned - some thread panic-ed on data send / receive
+    /// Channel to get data from logs is poisoned - some thread panic-ed on data send / receive.
+    /// Output is captured per call now, so it's not returned anymore.
     DataChannelPoisoned,
     /// Serde error re-mapped - contains serde error and original string
     /// so the outer scope could still process it
     JsonDeserializationFailed((serde_json::Error, String)),
+    /// Command didn't complete in time, see [Lvm::run_with_timeout].
+    /// It's never returned by lvm2cmd directly, so [CommandRetCode::from] doesn't produce it -
+    /// unrecognized native codes are still [CommandRetCode::Unknown]
+    Timeout,
+    /// All attempts of [Lvm::run_with_retry] failed, contains the last error
+    RetriesExhausted(Box<CommandRetCode>),
+    /// Command printed something which couldn't be parsed, contains the output
+    UnexpectedOutput(String),
+    /// VG can't be exported / imported while any of its LVs is active, see [Lvm::vg_export]
+    VgHasActiveLvs,
+    /// Installed LVM is too old for the requested feature, see [crate::version::LvmVersion]
+    FeatureNotSupported,
+    /// VG lock is requested, but `lvmlockd` isn't running, see [Lvm::vg_lock]
+    LockManagerNotAvailable,
+    /// Closure of [Lvm::acquire_and] panicked, contains the panic message
+    ClosurePanicked(String),
+    /// [Lvm::init_with] is called after the singleton got initialized
+    AlreadyInitialized,
+    /// Not an error - LVM ran in partial mode, so data may be inconsistent, see [crate::partial::PartialOutput]
+    PartialMode,
+    /// LV isn't a valid snapshot, see [Lvm::snapshot_usage]
+    SnapshotInvalid,
+    /// VG has no free space for the operation, see [Lvm::lvextend_to_fill_vg]
+    InsufficientFreeSpace,
+    /// JSON pointer doesn't match anything in the output, contains the pointer, see [Lvm::run_json_pointer]
+    JsonPointerNotFound(String),
+    /// Operation would destroy data of an LV in use, see [Lvm::lvreduce]
+    DataLossRisk,
 }
 
 impl From<i32> for CommandRetCode {
@@ -178,8 +869,444 @@ impl From<i32> for CommandRetCode {
     }
 }
 
-#[derive(Debug)]
-enum LogLevel {
+/// # Code for [CommandRetCode]
+/// The reverse of [CommandRetCode::from] for native codes, wrapper's own errors are 10 and above
+impl From<CommandRetCode> for i32 {
+    fn from(v: CommandRetCode) -> Self {
+        match v {
+            CommandRetCode::CommandSucceeded => 1,
+            CommandRetCode::NoSuchCommand => 2,
+            CommandRetCode::InvalidParameters => 3,
+            CommandRetCode::InitFailed => 4,
+            CommandRetCode::ProcessingFailed => 5,
+            CommandRetCode::Unknown(v) => v,
+            CommandRetCode::InvalidCommandLine(_) => 10,
+            CommandRetCode::GlobalStatePoisoned => 11,
+            CommandRetCode::DataChannelPoisoned => 12,
+            CommandRetCode::JsonDeserializationFailed(_) => 13,
+            CommandRetCode::Timeout => 14,
+            CommandRetCode::RetriesExhausted(_) => 15,
+            CommandRetCode::UnexpectedOutput(_) => 16,
+            CommandRetCode::VgHasActiveLvs => 17,
+            CommandRetCode::FeatureNotSupported => 18,
+            CommandRetCode::LockManagerNotAvailable => 19,
+            CommandRetCode::ClosurePanicked(_) => 20,
+            CommandRetCode::AlreadyInitialized => 21,
+            CommandRetCode::PartialMode => 22,
+            CommandRetCode::SnapshotInvalid => 23,
+            CommandRetCode::InsufficientFreeSpace => 24,
+            CommandRetCode::JsonPointerNotFound(_) => 25,
+            CommandRetCode::DataLossRisk => 26,
+        }
+    }
+}
+
+/// # Process exit code the way `lvm` binary exits
+/// [CommandRetCode::CommandSucceeded] is success, the rest exit with their [i32] code,
+/// unknown codes out of `1..=255` exit with 255.
+impl From<CommandRetCode> for ExitCode {
+    fn from(v: CommandRetCode) -> Self {
+        match i32::from(v) {
+            1 => ExitCode::SUCCESS,
+            code => ExitCode::from(
+                u8::try_from(code)
+                    .ok()
+                    .filter(|c| *c != 0)
+                    .unwrap_or(u8::MAX),
+            ),
+        }
+    }
+}
+
+impl CommandRetCode {
+    /// # Whether the same command could succeed if it's run again
+    /// - [CommandRetCode::ProcessingFailed] - often a transient failure, e.g. lock contention
+    /// - [CommandRetCode::Unknown] - codes of newer LVM versions, which may be transient
+    /// - [CommandRetCode::Timeout] - LVM was busy, e.g. waiting for a device lock
+    ///
+    /// The rest are permanent:
+    /// - [CommandRetCode::InitFailed], [CommandRetCode::GlobalStatePoisoned], [CommandRetCode::DataChannelPoisoned] - the wrapper is unusable
+    /// - [CommandRetCode::NoSuchCommand], [CommandRetCode::InvalidParameters], [CommandRetCode::InvalidCommandLine] - the command itself is wrong
+    /// - [CommandRetCode::JsonDeserializationFailed], [CommandRetCode::UnexpectedOutput] - the same output is expected next time
+    /// - [CommandRetCode::RetriesExhausted] - retries already happened
+    /// - [CommandRetCode::VgHasActiveLvs] - LVs have to be deactivated first
+    /// - [CommandRetCode::FeatureNotSupported] - LVM has to be upgraded
+    /// - [CommandRetCode::LockManagerNotAvailable] - `lvmlockd` has to be started
+    /// - [CommandRetCode::ClosurePanicked] - a bug of the caller
+    /// - [CommandRetCode::AlreadyInitialized] - it's too late to init with other options
+    /// - [CommandRetCode::PartialMode] - not an error
+    /// - [CommandRetCode::SnapshotInvalid] - the snapshot won't get valid by itself
+    /// - [CommandRetCode::InsufficientFreeSpace] - the VG has to be extended first
+    /// - [CommandRetCode::JsonPointerNotFound] - the pointer is wrong or the object doesn't exist
+    /// - [CommandRetCode::DataLossRisk] - the LV has to be released or the operation forced
+    /// - [CommandRetCode::CommandSucceeded] - not an error
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::ProcessingFailed | Self::Unknown(_) | Self::Timeout => true,
+            Self::CommandSucceeded
+            | Self::NoSuchCommand
+            | Self::InvalidParameters
+            | Self::InitFailed
+            | Self::InvalidCommandLine(_)
+            | Self::GlobalStatePoisoned
+            | Self::DataChannelPoisoned
+            | Self::JsonDeserializationFailed(_)
+            | Self::RetriesExhausted(_)
+            | Self::UnexpectedOutput(_)
+            | Self::VgHasActiveLvs
+            | Self::FeatureNotSupported
+            | Self::LockManagerNotAvailable
+            | Self::ClosurePanicked(_)
+            | Self::AlreadyInitialized
+            | Self::PartialMode
+            | Self::SnapshotInvalid
+            | Self::InsufficientFreeSpace
+            | Self::JsonPointerNotFound(_)
+            | Self::DataLossRisk => false,
+        }
+    }
+}
+
+impl Display for CommandRetCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CommandSucceeded => write!(f, "LVM command succeeded"),
+            Self::NoSuchCommand => write!(f, "no such LVM command"),
+            Self::InvalidParameters => write!(f, "invalid parameters for LVM command"),
+            Self::InitFailed => write!(f, "LVM initialization failed"),
+            Self::ProcessingFailed => write!(f, "LVM command processing failed"),
+            Self::Unknown(code) => write!(f, "unknown LVM return code {code}"),
+            Self::InvalidCommandLine(e) => write!(f, "invalid command line: {e}"),
+            Self::GlobalStatePoisoned => write!(f, "global LVM state is poisoned"),
+            Self::DataChannelPoisoned => write!(f, "LVM data channel is poisoned"),
+            Self::JsonDeserializationFailed((e, _)) => {
+                write!(f, "failed to deserialize LVM output: {e}")
+            }
+            Self::Timeout => write!(f, "LVM command timed out"),
+            Self::RetriesExhausted(e) => write!(f, "retries exhausted, last error: {e}"),
+            Self::UnexpectedOutput(output) => write!(f, "unexpected LVM output: {output}"),
+            Self::VgHasActiveLvs => write!(f, "volume group has active logical volumes"),
+            Self::FeatureNotSupported => write!(f, "feature is not supported by this LVM version"),
+            Self::LockManagerNotAvailable => write!(f, "lvmlockd is not running"),
+            Self::ClosurePanicked(message) => write!(f, "closure panicked: {message}"),
+            Self::AlreadyInitialized => write!(f, "LVM is already initialized"),
+            Self::PartialMode => write!(f, "LVM ran in partial mode, data may be inconsistent"),
+            Self::SnapshotInvalid => write!(f, "logical volume isn't a valid snapshot"),
+            Self::InsufficientFreeSpace => write!(f, "volume group has no free space"),
+            Self::JsonPointerNotFound(pointer) => {
+                write!(f, "JSON pointer {pointer:?} not found in LVM output")
+            }
+            Self::DataLossRisk => write!(f, "operation may destroy data of the logical volume"),
+        }
+    }
+}
+
+impl Error for CommandRetCode {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidCommandLine(e) => Some(e),
+            Self::JsonDeserializationFailed((e, _)) => Some(e),
+            Self::RetriesExhausted(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl Clone for CommandRetCode {
+    fn clone(&self) -> Self {
+        match self {
+            Self::CommandSucceeded => Self::CommandSucceeded,
+            Self::NoSuchCommand => Self::NoSuchCommand,
+            Self::InvalidParameters => Self::InvalidParameters,
+            Self::InitFailed => Self::InitFailed,
+            Self::ProcessingFailed => Self::ProcessingFailed,
+            Self::Unknown(code) => Self::Unknown(*code),
+            Self::InvalidCommandLine(e) => Self::InvalidCommandLine(e.clone()),
+            Self::GlobalStatePoisoned => Self::GlobalStatePoisoned,
+            Self::DataChannelPoisoned => Self::DataChannelPoisoned,
+            Self::JsonDeserializationFailed((e, data)) => {
+                Self::JsonDeserializationFailed((clone_json_error(e, data), data.clone()))
+            }
+            Self::Timeout => Self::Timeout,
+            Self::RetriesExhausted(e) => Self::RetriesExhausted(e.clone()),
+            Self::UnexpectedOutput(output) => Self::UnexpectedOutput(output.clone()),
+            Self::VgHasActiveLvs => Self::VgHasActiveLvs,
+            Self::FeatureNotSupported => Self::FeatureNotSupported,
+            Self::LockManagerNotAvailable => Self::LockManagerNotAvailable,
+            Self::ClosurePanicked(message) => Self::ClosurePanicked(message.clone()),
+            Self::AlreadyInitialized => Self::AlreadyInitialized,
+            Self::PartialMode => Self::PartialMode,
+            Self::SnapshotInvalid => Self::SnapshotInvalid,
+            Self::InsufficientFreeSpace => Self::InsufficientFreeSpace,
+            Self::JsonPointerNotFound(pointer) => Self::JsonPointerNotFound(pointer.clone()),
+            Self::DataLossRisk => Self::DataLossRisk,
+        }
+    }
+}
+
+impl PartialEq for CommandRetCode {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Unknown(a), Self::Unknown(b)) => a == b,
+            (Self::InvalidCommandLine(a), Self::InvalidCommandLine(b)) => a == b,
+            (
+                Self::JsonDeserializationFailed((a, a_data)),
+                Self::JsonDeserializationFailed((b, b_data)),
+            ) => a.classify() == b.classify() && a_data == b_data,
+            (Self::RetriesExhausted(a), Self::RetriesExhausted(b)) => a == b,
+            (Self::UnexpectedOutput(a), Self::UnexpectedOutput(b)) => a == b,
+            (Self::ClosurePanicked(a), Self::ClosurePanicked(b)) => a == b,
+            (Self::JsonPointerNotFound(a), Self::JsonPointerNotFound(b)) => a == b,
+            // the rest have no data
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for CommandRetCode {}
+
+/// Consistent with [PartialEq]: [CommandRetCode::InvalidCommandLine] hashes its display string,
+/// [CommandRetCode::JsonDeserializationFailed] - the error category and the data, as its message isn't compared
+impl Hash for CommandRetCode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Unknown(code) => code.hash(state),
+            Self::InvalidCommandLine(e) => e.to_string().hash(state),
+            Self::JsonDeserializationFailed((e, data)) => {
+                format!("{:?}", e.classify()).hash(state);
+                data.hash(state);
+            }
+            Self::RetriesExhausted(e) => e.hash(state),
+            Self::UnexpectedOutput(output) => output.hash(state),
+            Self::ClosurePanicked(message) => message.hash(state),
+            Self::JsonPointerNotFound(pointer) => pointer.hash(state),
+            // the rest have no data
+            _ => (),
+        }
+    }
+}
+
+/// `{"kind": "<variant>", ...}` with variant's data as other fields:
+/// `code` of [CommandRetCode::Unknown], `message` of [CommandRetCode::InvalidCommandLine],
+/// `cause` and `raw` of [CommandRetCode::JsonDeserializationFailed], `last` of [CommandRetCode::RetriesExhausted]
+/// `output` of [CommandRetCode::UnexpectedOutput], `message` of [CommandRetCode::ClosurePanicked]
+/// and `pointer` of [CommandRetCode::JsonPointerNotFound]
+#[cfg(feature = "serde")]
+impl serde::Serialize for CommandRetCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let kind = match self {
+            Self::CommandSucceeded => "CommandSucceeded",
+            Self::NoSuchCommand => "NoSuchCommand",
+            Self::InvalidParameters => "InvalidParameters",
+            Self::InitFailed => "InitFailed",
+            Self::ProcessingFailed => "ProcessingFailed",
+            Self::Unknown(_) => "Unknown",
+            Self::InvalidCommandLine(_) => "InvalidCommandLine",
+            Self::GlobalStatePoisoned => "GlobalStatePoisoned",
+            Self::DataChannelPoisoned => "DataChannelPoisoned",
+            Self::JsonDeserializationFailed(_) => "JsonDeserializationFailed",
+            Self::Timeout => "Timeout",
+            Self::RetriesExhausted(_) => "RetriesExhausted",
+            Self::UnexpectedOutput(_) => "UnexpectedOutput",
+            Self::VgHasActiveLvs => "VgHasActiveLvs",
+            Self::FeatureNotSupported => "FeatureNotSupported",
+            Self::LockManagerNotAvailable => "LockManagerNotAvailable",
+            Self::ClosurePanicked(_) => "ClosurePanicked",
+            Self::AlreadyInitialized => "AlreadyInitialized",
+            Self::PartialMode => "PartialMode",
+            Self::SnapshotInvalid => "SnapshotInvalid",
+            Self::InsufficientFreeSpace => "InsufficientFreeSpace",
+            Self::JsonPointerNotFound(_) => "JsonPointerNotFound",
+            Self::DataLossRisk => "DataLossRisk",
+        };
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", kind)?;
+        match self {
+            Self::Unknown(code) => map.serialize_entry("code", code)?,
+            Self::InvalidCommandLine(e) => map.serialize_entry("message", &e.to_string())?,
+            Self::JsonDeserializationFailed((e, data)) => {
+                map.serialize_entry("cause", &e.to_string())?;
+                map.serialize_entry("raw", data)?;
+            }
+            Self::RetriesExhausted(e) => map.serialize_entry("last", e)?,
+            Self::UnexpectedOutput(output) => map.serialize_entry("output", output)?,
+            Self::ClosurePanicked(message) => map.serialize_entry("message", message)?,
+            Self::JsonPointerNotFound(pointer) => map.serialize_entry("pointer", pointer)?,
+            _ => (),
+        }
+        map.end()
+    }
+}
+
+/// Message of `panic!`, which payload is either `&str` or `String` unless it's [std::panic::panic_any]
+pub(crate) fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_payload) => "<non-string panic payload>".to_string(),
+        },
+    }
+}
+
+/// # Re-create JSON error of the same category
+/// Syntax errors are reproduced by parsing the data again, errors of typed deserialization keep only the message
+fn clone_json_error(e: &serde_json::Error, data: &str) -> serde_json::Error {
+    match serde_json::from_str::<serde::de::IgnoredAny>(data) {
+        Err(reparsed) if reparsed.classify() == e.classify() => reparsed,
+        _ => <serde_json::Error as serde::de::Error>::custom(e),
+    }
+}
+
+/// # Settings LVM reads on initialization
+/// See [Lvm::init_with]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LvmInitOptions {
+    system_dir: Option<PathBuf>,
+    config_profile: Option<String>,
+}
+
+impl LvmInitOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Directory with `lvm.conf` instead of `/etc/lvm`, set as `LVM_SYSTEM_DIR`
+    pub fn system_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.system_dir = Some(dir.into());
+        self
+    }
+
+    /// Name of a command profile from `profile_dir`, set as `LVM_COMMAND_PROFILE`
+    pub fn config_profile(mut self, profile: &str) -> Self {
+        self.config_profile = Some(profile.to_string());
+        self
+    }
+
+    /// environment variables to set, NUL would make [std::env::set_var] panic
+    fn env(&self) -> Result<Vec<(&'static str, &OsStr)>, CommandRetCode> {
+        let mut env = vec![];
+        if let Some(dir) = &self.system_dir {
+            env.push(("LVM_SYSTEM_DIR", dir.as_os_str()));
+        }
+        if let Some(profile) = &self.config_profile {
+            if profile.is_empty() || profile.contains('/') {
+                return Err(CommandRetCode::InvalidParameters);
+            }
+            env.push(("LVM_COMMAND_PROFILE", OsStr::new(profile)));
+        }
+        if env
+            .iter()
+            .any(|(_name, value)| value.as_encoded_bytes().contains(&0))
+        {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        Ok(env)
+    }
+}
+
+/// # Run `f` with the environment variables set
+/// Previous values are restored afterwards, variables which weren't set are removed.
+///
+/// # Safety
+/// Same as for [std::env::set_var]
+unsafe fn with_env<Y>(env: &[(&str, &OsStr)], f: impl FnOnce() -> Y) -> Y {
+    let saved: Vec<_> = env
+        .iter()
+        .map(|(name, value)| {
+            let previous = std::env::var_os(name);
+            // SAFETY: it's on the caller
+            unsafe { std::env::set_var(name, value) };
+            (name, previous)
+        })
+        .collect();
+    let res = f();
+    for (name, previous) in saved.into_iter().rev() {
+        // SAFETY: it's on the caller
+        unsafe {
+            match previous {
+                Some(value) => std::env::set_var(name, value),
+                None => std::env::remove_var(name),
+            }
+        }
+    }
+    res
+}
+
+/// # Output format of [Lvm::run_format]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReportFormat {
+    /// `--reportformat json`, what [Lvm::run] parses
+    Json,
+    /// `--reportformat basic` - aligned columns with headings, as LVM prints by default
+    Basic,
+    /// `--reportformat basic --noheadings --separator :` - one line per object, handy for `cut` / `awk`
+    Columns,
+}
+
+impl ReportFormat {
+    /// flags selecting the format
+    fn flags(&self) -> &'static str {
+        match self {
+            Self::Json => DEFAULT_LVM_FLAGS,
+            Self::Basic => "--reportformat basic",
+            Self::Columns => "--reportformat basic --noheadings --separator :",
+        }
+    }
+}
+
+/// # Verbosity of [Lvm::run_with_verbosity]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VerbosityLevel {
+    /// no extra flags, only warnings and errors are logged
+    #[default]
+    Normal,
+    /// `-v`
+    Verbose,
+    /// `-vv`
+    VeryVerbose,
+    /// `-vvv`, debug messages
+    Debug,
+}
+
+impl VerbosityLevel {
+    /// flag to add to the command
+    fn flag(&self) -> Option<&'static str> {
+        match self {
+            Self::Normal => None,
+            Self::Verbose => Some("-v"),
+            Self::VeryVerbose => Some("-vv"),
+            Self::Debug => Some("-vvv"),
+        }
+    }
+}
+
+/// # LVM log message, see [Lvm::run_collecting_logs]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogMessage {
+    pub level: LogLevel,
+    /// LVM source file
+    pub file: String,
+    pub line: i32,
+    pub message: String,
+}
+
+/// # Receiver of LVM log messages
+/// See [Lvm::set_log_sink]
+pub trait LvmLogSink {
+    /// Called for each log line, `file` and `line` point to the LVM sources the message came from.
+    /// It's called from lvm2cmd's log callback, so panics are caught there, see [Lvm::set_log_sink].
+    /// Non-UTF-8 bytes are replaced with `U+FFFD`.
+    fn log(&self, level: LogLevel, file: &str, line: i32, message: &str);
+}
+
+/// # Levels of LVM log messages
+/// As defined in lvm2cmd.h
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
     FATAL,
     ERROR,
     PRINT,
@@ -203,43 +1330,189 @@ impl From<c_int> for LogLevel {
     }
 }
 
+impl LogLevel {
+    /// # BSD syslog priority of the level
+    /// lvm2cmd levels already are syslog ones: FATAL is LOG_CRIT (2), ERROR - LOG_ERR (3),
+    /// PRINT - LOG_WARNING (4), which LVM also uses for warnings, VERBOSE - LOG_NOTICE (5),
+    /// VERY_VERBOSE - LOG_INFO (6), DEBUG - LOG_DEBUG (7). UNKNOWN is LOG_NOTICE.
+    pub fn to_syslog_priority(&self) -> u8 {
+        match self {
+            Self::FATAL => 2,
+            Self::ERROR => 3,
+            Self::PRINT => 4,
+            Self::VERBOSE => 5,
+            Self::VERY_VERBOSE => 6,
+            Self::DEBUG => 7,
+            Self::UNKNOWN => 5,
+        }
+    }
+}
+
+impl LogLevel {
+    /// every level, in the lvm2cmd order
+    const ALL: [Self; 7] = [
+        Self::FATAL,
+        Self::ERROR,
+        Self::PRINT,
+        Self::VERBOSE,
+        Self::VERY_VERBOSE,
+        Self::DEBUG,
+        Self::UNKNOWN,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::FATAL => "FATAL",
+            Self::ERROR => "ERROR",
+            Self::PRINT => "PRINT",
+            Self::VERBOSE => "VERBOSE",
+            Self::VERY_VERBOSE => "VERY_VERBOSE",
+            Self::DEBUG => "DEBUG",
+            Self::UNKNOWN => "UNKNOWN",
+        }
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Log level string couldn't be parsed, contains the original string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLogLevelError(pub String);
+
+impl Display for ParseLogLevelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid LVM log level: {:?}", self.0)
+    }
+}
+
+impl Error for ParseLogLevelError {}
+
+impl FromStr for LogLevel {
+    type Err = ParseLogLevelError;
+
+    /// Names as [Display] prints them, case-insensitive, e.g. `verbose` or `VERY_VERBOSE`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| ParseLogLevelError(s.to_string()))
+    }
+}
+
 /// # Callback for LVM logs
-/// It capture commands execution results by collecting all PRINT logs and passing it to the channel
+/// It captures commands execution results by collecting all PRINT logs into the innermost frame of [CAPTURED_CMD_DATA]
 /// ASSUMPTION: the underlying code guarantees that this fn gets called sequentially for sequential lines
 ///             JSON doc will be incorrect otherwise
 extern "C" fn log_capturer(
     level: c_int,
     file: *const c_char,
-    _line: c_int,
+    line: c_int,
     _dm_errno: c_int,
     message: *const c_char,
 ) {
-    let mut cmd_output = CAPTURED_CMD_DATA.lock().unwrap(); // UNWRAP: this mutex panics if this method panics => can't rely on logs piping anymore
-
-    let message = unsafe { CStr::from_ptr(message) }.to_str().unwrap();
-    let file = unsafe { CStr::from_ptr(file) }.to_str().unwrap();
+    // panics can't unwind into lvm2cmd, so nothing here may panic
+    let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+    let file = unsafe { CStr::from_ptr(file) }.to_string_lossy();
+    let (message, file) = (message.as_ref(), file.as_ref());
     let level = LogLevel::from(level);
-    match (&level, file) {
-        (LogLevel::DEBUG, "lvmcmdline.c") => {
-            if message.starts_with("Completed:") {
-                // DEBUG "lvmcmdline.c" 3325 0 "Completed: pvs --reportformat json"
-                if cmd_output.is_empty() {
-                    // no PRINT messages
-                    cmd_output.push_str(r#"{"rust_logger": "no messages from command"}"#);
+    // messages not coming from Lvm::_run_logged, e.g. on init, aren't captured
+    CAPTURED_CMD_DATA.with_borrow_mut(|stack| {
+        if let Some(captured) = stack.last_mut() {
+            match level {
+                LogLevel::PRINT if !is_warning(message) => {
+                    captured.output.push_str(message);
+                    if captured.line_breaks {
+                        captured.output.push('\n');
+                    }
                 }
-                CHANNEL
-                    .lock()
-                    .unwrap()
-                    .0
-                    .send(cmd_output.drain(..).collect())
-                    .unwrap();
-                DATA_ARRIVED.notify_all();
+                _ => captured.logs.push(LogMessage {
+                    level,
+                    file: file.to_string(),
+                    line,
+                    message: message.to_string(),
+                }),
             }
         }
-        (LogLevel::PRINT, _) => cmd_output.push_str(message),
-        _ => (),
+    });
+
+    if let Some(sink) = log_sink() {
+        let logged = panic::catch_unwind(AssertUnwindSafe(|| sink.log(level, file, line, message)));
+        if let Err(payload) = logged {
+            // not to the sink, it'd likely panic again
+            eprintln!("lvm-sys2: log sink panicked: {}", panic_message(payload));
+        }
     }
+}
+
+/// Whether the PRINT message is a warning, they'd break the JSON output
+fn is_warning(message: &str) -> bool {
+    message.trim_start().starts_with("WARNING:")
+}
+
+/// FATAL / ERROR messages among the logs, they tell why a command failed
+fn error_messages(logs: &[LogMessage]) -> Vec<String> {
+    logs.iter()
+        .filter(|log| matches!(log.level, LogLevel::FATAL | LogLevel::ERROR))
+        .map(|log| log.message.clone())
+        .collect()
+}
+
+/// Whether error messages of a command tell the object already exists, see [Lvm::run_idempotent]
+fn is_already_existing(messages: &[String]) -> bool {
+    messages
+        .iter()
+        .any(|m| ALREADY_EXISTS_PATTERNS.iter().any(|p| m.contains(p)))
+}
+
+/// Sink registered with [Lvm::set_log_sink], cloned to not hold the lock while the sink works
+fn log_sink() -> Option<Arc<dyn LvmLogSink + Send + Sync>> {
+    LOG_SINK.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
 
-    // TODO: allow to write to some log file instead
-    // println!("--> {:?} {:?} {line} {dm_errno} {:?}", level, file, message);
+/// Report crate's own problem which can't be returned to the caller, e.g. in [Drop].
+/// Goes to the log sink if there's one, to stderr otherwise.
+pub(crate) fn log_error(file: &str, line: u32, message: &str) {
+    log_own(LogLevel::ERROR, file, line, message);
+}
+
+/// Warn about something dangerous, the same way as [log_error].
+/// LVM itself logs warnings with PRINT level.
+pub(crate) fn log_warning(file: &str, line: u32, message: &str) {
+    log_own(LogLevel::PRINT, file, line, message);
+}
+
+fn log_own(level: LogLevel, file: &str, line: u32, message: &str) {
+    match log_sink() {
+        Some(sink) => sink.log(level, file, line as i32, message),
+        None => eprintln!("lvm-sys2: {message}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn already_existing() {
+        let messages =
+            |messages: &[&str]| messages.iter().map(|m| m.to_string()).collect::<Vec<_>>();
+        assert!(is_already_existing(&messages(&[
+            "A volume group called vg0 already exists."
+        ])));
+        assert!(is_already_existing(&messages(&[
+            "Run `lvcreate --help' for more information.",
+            "Logical Volume \"data\" already exists in volume group \"vg0\"",
+        ])));
+        assert!(is_already_existing(&messages(&[
+            "/dev/sdb is already a physical volume"
+        ])));
+        assert!(!is_already_existing(&messages(&[
+            "Volume group \"vg0\" not found"
+        ])));
+        assert!(!is_already_existing(&[]));
+    }
 }
@@ -0,0 +1,123 @@
+//! Canned LVM responses for tests of crates using this one, behind the `test-utils` feature.
+//!
+//! ```
+//! use lvm_sys2::{lvm::Lvm, mock::MockLvm};
+//!
+//! let _mock = MockLvm::with_standard_fixtures().respond("lvremove 'vg0/data'", Ok(String::new()));
+//! assert_eq!(Lvm::vgs().unwrap()[0].vg_name, "vg0");
+//! assert!(Lvm::lv().lvremove("vg0", "data", false).is_ok());
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    marker::PhantomData,
+};
+
+use crate::lvm::{CommandRetCode, NO_OUTPUT};
+
+/// `pvs` output with a single PV `/dev/sdb` in `vg0`
+pub const PVS_FIXTURE: &str = r#"{"report":[{"pv":[{"pv_name":"/dev/sdb","vg_name":"vg0","pv_fmt":"lvm2","pv_attr":"a--","pv_size":"<10.00g","pv_free":"<6.00g"}]}]}"#;
+
+/// `vgs` output with a single VG `vg0` of [PVS_FIXTURE]
+pub const VGS_FIXTURE: &str = r#"{"report":[{"vg":[{"vg_name":"vg0","pv_count":"1","lv_count":"1","snap_count":"0","vg_attr":"wz--n-","vg_size":"<10.00g","vg_free":"<6.00g"}]}]}"#;
+
+/// `lvs` output with a single active LV `vg0/data`
+pub const LVS_FIXTURE: &str = r#"{"report":[{"lv":[{"lv_name":"data","vg_name":"vg0","lv_attr":"-wi-a-----","lv_size":"4.00g","pool_lv":"","origin":"","data_percent":"","metadata_percent":"","move_pv":"","mirror_log":"","copy_percent":"","convert_lv":""}]}]}"#;
+
+type Responses = HashMap<String, Result<String, CommandRetCode>>;
+
+thread_local! {
+    /// responses of the [MockLvm] alive on this thread
+    static RESPONSES: RefCell<Option<Responses>> = const { RefCell::new(None) };
+    /// generation of the last [MockLvm] created on this thread, the one [RESPONSES] belong to
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+/// # Replaces LVM with canned responses
+/// While it's alive, [crate::lvm::Lvm] calls on the same thread don't reach lvm2cmd,
+/// they return what is registered for the command with [MockLvm::respond] instead.
+/// Commands are matched as passed to [crate::lvm::Lvm::run], without the default flags,
+/// unknown ones result into [CommandRetCode::NoSuchCommand].
+///
+/// Responses are thread-local, so each test gets its own, but calls going to other threads,
+/// e.g. [crate::lvm::Lvm::run_with_timeout], aren't mocked. Creating another mock on the same thread replaces the current one.
+pub struct MockLvm {
+    /// tells whether the mock is still the installed one, see [GENERATION]
+    generation: u64,
+    // responses are bound to the thread, so is the mock
+    _not_send: PhantomData<*const ()>,
+}
+
+impl MockLvm {
+    /// # Mock without any responses
+    pub fn new() -> Self {
+        let generation = GENERATION.get() + 1;
+        GENERATION.set(generation);
+        RESPONSES.set(Some(HashMap::new()));
+        Self {
+            generation,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// # Mock knowing `pvs`, `vgs` and `lvs`
+    /// They print [PVS_FIXTURE], [VGS_FIXTURE] and [LVS_FIXTURE] respectively.
+    pub fn with_standard_fixtures() -> Self {
+        Self::new()
+            .respond("pvs", Ok(PVS_FIXTURE.to_string()))
+            .respond("vgs", Ok(VGS_FIXTURE.to_string()))
+            .respond("lvs", Ok(LVS_FIXTURE.to_string()))
+    }
+
+    /// # Register a response to the command
+    /// `Ok` is the command's output, e.g. a JSON report, empty one stands for a command printing nothing.
+    /// Replaces the previous response to the same command. It's a no-op if another mock replaced this one.
+    pub fn respond(self, command: &str, response: Result<String, CommandRetCode>) -> Self {
+        if self.is_installed() {
+            RESPONSES.with_borrow_mut(|responses| {
+                if let Some(responses) = responses {
+                    responses.insert(command.trim().to_string(), response);
+                }
+            });
+        }
+        self
+    }
+
+    /// whether no mock was created on this thread after this one
+    fn is_installed(&self) -> bool {
+        GENERATION.get() == self.generation
+    }
+}
+
+impl Default for MockLvm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for MockLvm {
+    /// Responses of a mock which replaced this one stay, e.g. on `mock = MockLvm::new()`
+    fn drop(&mut self) {
+        if self.is_installed() {
+            RESPONSES.set(None);
+        }
+    }
+}
+
+/// whether a mock is alive on this thread
+pub(crate) fn is_active() -> bool {
+    RESPONSES.with_borrow(Option::is_some)
+}
+
+/// response to the command if a mock is alive on this thread, same as lvm2cmd's output
+pub(crate) fn response(command: &str) -> Option<Result<String, CommandRetCode>> {
+    RESPONSES.with_borrow(|responses| {
+        let responses = responses.as_ref()?;
+        Some(match responses.get(command.trim()) {
+            Some(Ok(output)) if output.is_empty() => Ok(NO_OUTPUT.to_string()),
+            Some(response) => response.clone(),
+            None => Err(CommandRetCode::NoSuchCommand),
+        })
+    })
+}
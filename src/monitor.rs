@@ -0,0 +1,71 @@
+//! dmeventd monitoring of LVs, see [Lvm::register_event] and `man 8 dmeventd`.
+
+use crate::{
+    lv::lv_path_arg,
+    lvm::{CommandRetCode, Lvm},
+    report::lvs_from,
+};
+
+/// # Event dmeventd watches for
+/// dmeventd picks the plugin by LV type, so an event is only available for LVs of its type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DmEvent {
+    /// Thin pool data / metadata is filling up, see `thin_pool_autoextend_threshold` in lvm.conf
+    ThinPoolSpaceUsage,
+    /// RAID image failed, see `raid_fault_policy` in lvm.conf
+    RaidDegradation,
+    /// Snapshot is filling up, see `snapshot_autoextend_threshold` in lvm.conf
+    SnapshotUsage,
+}
+
+impl DmEvent {
+    /// Whether the LV with such `lv_attr` emits the event, the 1st character is the volume type
+    fn applies_to(&self, lv_attr: &str) -> bool {
+        let types: &[char] = match self {
+            Self::ThinPoolSpaceUsage => &['t'],
+            Self::RaidDegradation => &['r', 'R'],
+            Self::SnapshotUsage => &['s', 'S'],
+        };
+        lv_attr.starts_with(types)
+    }
+}
+
+impl Lvm {
+    /// # Start dmeventd monitoring of `VG/LV`
+    /// Checks that the LV has the type of every event, then runs `lvchange --monitor y VG/LV`.
+    /// Long-running programs can rely on dmeventd reacting on the events (e.g. autoextending a thin pool)
+    /// instead of polling `lvs`.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if no events are given or the LV is of another type.
+    pub fn register_event(vg: &str, lv: &str, events: &[DmEvent]) -> Result<(), CommandRetCode> {
+        let lv_arg = lv_path_arg(vg, lv)?;
+        if events.is_empty() {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        let command = format!("lvs {lv_arg}");
+        let info = lvs_from(&command)?
+            .into_iter()
+            .next()
+            .ok_or(CommandRetCode::UnexpectedOutput(command))?;
+        if !events.iter().all(|event| event.applies_to(&info.lv_attr)) {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        Lvm::run_quiet(&monitor_command(vg, lv, true)?)
+    }
+
+    /// # Stop dmeventd monitoring of `VG/LV`
+    /// Runs `lvchange --monitor n VG/LV`, all events of the LV stop being handled
+    pub fn unregister_event(vg: &str, lv: &str) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&monitor_command(vg, lv, false)?)
+    }
+}
+
+/// # `lvchange --monitor` command
+/// See [Lvm::register_event]
+pub(crate) fn monitor_command(vg: &str, lv: &str, monitor: bool) -> Result<String, CommandRetCode> {
+    let monitor = if monitor { "y" } else { "n" };
+    Ok(format!(
+        "lvchange --monitor {monitor} {}",
+        lv_path_arg(vg, lv)?
+    ))
+}
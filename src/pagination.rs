@@ -0,0 +1,62 @@
+//! Paging through large reports, see [Lvm::run_paginated].
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+use crate::{
+    lvm::{CommandRetCode, Lvm},
+    report::{Report, parse_report},
+};
+
+/// # Page of report rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaginatedResult<T> {
+    pub items: Vec<T>,
+    /// 0-based
+    pub page: usize,
+    pub page_size: usize,
+    /// whether there are rows after this page
+    pub has_more: bool,
+}
+
+impl Lvm {
+    /// # Run LVM report command and return a page of its rows
+    /// Rows of all reports in the output, e.g. `lv` objects of `lvs`, are split into pages of `page_size`,
+    /// `page` is 0-based. Pages past the end are empty.
+    ///
+    /// LVM reporting has no options to skip or limit rows, so the whole report is fetched and sliced on the Rust side.
+    /// Combine it with [Lvm::run_sorted] for the pages to be stable across calls, and with `--select` or
+    /// [Lvm::run_fields] to keep the output of large setups small.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if `page_size` is 0.
+    pub fn run_paginated(
+        command: &str,
+        page: usize,
+        page_size: usize,
+    ) -> Result<PaginatedResult<Map<String, Value>>, CommandRetCode> {
+        if page_size == 0 {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        let reports: Report<BTreeMap<String, Vec<Map<String, Value>>>> =
+            parse_report(Value::Object(Self::run(command)?))?;
+        let rows = reports
+            .report
+            .into_iter()
+            .flat_map(|report| report.into_values().flatten())
+            .collect();
+        Ok(paginate(rows, page, page_size))
+    }
+}
+
+/// Split rows into pages of non-zero `page_size` and take one
+fn paginate<T>(rows: Vec<T>, page: usize, page_size: usize) -> PaginatedResult<T> {
+    let start = page.saturating_mul(page_size);
+    let has_more = rows.len() > start.saturating_add(page_size);
+    PaginatedResult {
+        items: rows.into_iter().skip(start).take(page_size).collect(),
+        page,
+        page_size,
+        has_more,
+    }
+}
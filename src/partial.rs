@@ -0,0 +1,74 @@
+//! Partial mode for recovery of VGs with missing PVs, see [Lvm::run_partial].
+
+use serde_json::Value;
+
+use crate::{
+    lvm::{CommandRetCode, Lvm},
+    report::{LvInfo, LvReport, PvInfo, PvReport, Report, VgInfo, VgReport, parse_report},
+};
+
+/// LVM prints it before the report when it runs in partial mode
+const PARTIAL_MODE_MESSAGE: &str = "PARTIAL MODE. Incomplete logical volumes will be processed.";
+
+/// # Output of a command run with `--partial`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialOutput<T> {
+    pub output: T,
+    /// [CommandRetCode::PartialMode] if LVM reported it's in partial mode, i.e. some PVs are missing
+    pub advisory: Option<CommandRetCode>,
+}
+
+impl Lvm {
+    /// # Run LVM command in partial mode
+    /// Same as [Lvm::run] with `--partial`, which makes LVM process VGs with missing PVs instead of refusing to.
+    ///
+    /// It's intended for recovery workflows only: data of LVs on missing PVs is incomplete,
+    /// so the report may be inconsistent.
+    pub fn run_partial(
+        command: &str,
+    ) -> Result<PartialOutput<serde_json::Map<String, Value>>, CommandRetCode> {
+        let output = Self::run_raw(&format!("{command} --partial"))?;
+        // the message comes as PRINT, so it's glued to the report
+        let (data, advisory) = match output.split_once(PARTIAL_MODE_MESSAGE) {
+            Some((before, after)) => (
+                format!("{before}{after}"),
+                Some(CommandRetCode::PartialMode),
+            ),
+            None => (output, None),
+        };
+        let output = serde_json::from_str(&data)
+            .map_err(|e| CommandRetCode::JsonDeserializationFailed((e, data)))?;
+        Ok(PartialOutput { output, advisory })
+    }
+
+    /// # List physical volumes including missing ones
+    /// Runs `pvs` in partial mode (see [Lvm::run_partial]), so PVs of degraded VGs are reported
+    /// instead of failing. Missing ones are [PvInfo::is_missing], e.g. for health monitoring to alert on.
+    pub fn pvs_with_missing() -> Result<Vec<PvInfo>, CommandRetCode> {
+        let PartialOutput { output, .. } = Self::run_partial("pvs")?;
+        let reports: Report<PvReport> = parse_report(Value::Object(output))?;
+        Ok(reports.report.into_iter().flat_map(|r| r.pv).collect())
+    }
+
+    /// # List volume groups in partial mode
+    /// Same as [Lvm::vgs], see [Lvm::run_partial] for the caveats
+    pub fn vgs_partial() -> Result<PartialOutput<Vec<VgInfo>>, CommandRetCode> {
+        let PartialOutput { output, advisory } = Self::run_partial("vgs")?;
+        let reports: Report<VgReport> = parse_report(Value::Object(output))?;
+        Ok(PartialOutput {
+            output: reports.report.into_iter().flat_map(|r| r.vg).collect(),
+            advisory,
+        })
+    }
+
+    /// # List logical volumes in partial mode
+    /// Same as [Lvm::lvs], see [Lvm::run_partial] for the caveats
+    pub fn lvs_partial() -> Result<PartialOutput<Vec<LvInfo>>, CommandRetCode> {
+        let PartialOutput { output, advisory } = Self::run_partial("lvs")?;
+        let reports: Report<LvReport> = parse_report(Value::Object(output))?;
+        Ok(PartialOutput {
+            output: reports.report.into_iter().flat_map(|r| r.lv).collect(),
+            advisory,
+        })
+    }
+}
@@ -0,0 +1,238 @@
+//! Physical volumes management, see [PvManager].
+
+use std::{
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    lvm::{CommandRetCode, Lvm, panic_message},
+    report::{PvInfo, lvs_from, pvs_from, select_arg},
+    units::ByteSize,
+};
+
+/// How often [Lvm::pvmove_async] checks the progress
+const PVMOVE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// # Typed wrappers for PV commands
+/// Obtained with [Lvm::pv].
+/// Devices are checked to exist before running a command, [CommandRetCode::InvalidParameters] is returned otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PvManager;
+
+impl Lvm {
+    /// Access PV commands
+    pub fn pv() -> PvManager {
+        PvManager
+    }
+
+    /// # Move extents off `src` without blocking
+    /// Starts `pvmove -b <src> [dst]`, which moves the data in background, and spawns a thread polling its progress.
+    /// LVM moves data through a temporary mirror LV, so the progress is taken from its `copy_percent`
+    /// and the move is complete once the LV is gone.
+    /// `progress` is called from the polling thread every couple of seconds and once more on completion.
+    ///
+    /// The move goes on even if the returned [PvMoveHandle] is dropped.
+    pub fn pvmove_async(
+        src: &Path,
+        dst: Option<&Path>,
+        progress: impl Fn(PvMoveProgress) + Send + 'static,
+    ) -> Result<PvMoveHandle, CommandRetCode> {
+        let src_arg = device_arg(src)?;
+        let command = match dst {
+            Some(dst) => format!("pvmove -b {src_arg} {}", device_arg(dst)?),
+            None => format!("pvmove -b {src_arg}"),
+        };
+        let total_extents = Self::pvdisplay(src)?.pv_pe_alloc_count;
+        Lvm::run_quiet(&command)?;
+
+        let src = src_arg.to_string();
+        let aborted = Arc::new(AtomicBool::new(false));
+        let thread_aborted = aborted.clone();
+        let thread = thread::spawn(move || {
+            let moving = format!("lvs -a {}", select_arg(&format!("move_pv={src}"))?);
+            while !thread_aborted.load(Ordering::SeqCst) {
+                let lvs = lvs_from(&moving)?;
+                if lvs.is_empty() {
+                    progress(PvMoveProgress {
+                        moved_extents: total_extents,
+                        total_extents,
+                    });
+                    break;
+                }
+                let percent = lvs.iter().filter_map(|lv| lv.copy_percent).next();
+                progress(PvMoveProgress {
+                    moved_extents: (total_extents as f64 * percent.unwrap_or(0.0) / 100.0) as u64,
+                    total_extents,
+                });
+                thread::sleep(PVMOVE_POLL_INTERVAL);
+            }
+            Ok(())
+        });
+        Ok(PvMoveHandle {
+            src: src_arg.to_string(),
+            aborted,
+            thread,
+        })
+    }
+
+    /// # Scan devices for PVs
+    /// Runs `pvscan [--cache] [--activate ay]`. LVM accepts `--activate` only along with `--cache`.
+    /// The command prints no report, so only success is reported.
+    pub fn pvscan(activate: bool, cache: bool) -> Result<(), CommandRetCode> {
+        let mut command = "pvscan".to_string();
+        if cache {
+            command.push_str(" --cache");
+        }
+        if activate {
+            command.push_str(" --activate ay");
+        }
+        Self::run_quiet(&command)
+    }
+
+    /// # Wipe signatures off a device to put a PV on it
+    /// `wipefs` isn't a part of lvm2cmd, so it runs `pvcreate --yes --zero y --wipesignatures <y|n>` instead.
+    /// Unlike `wipefs -a`, the device ends up being a PV.
+    ///
+    /// With `all` every known signature (filesystems, RAID, partition tables) is wiped,
+    /// otherwise only the first sectors are zeroed, which destroys the signature at the start of the device.
+    pub fn wipesignatures(device: &Path, all: bool) -> Result<(), CommandRetCode> {
+        let wipe = if all { "y" } else { "n" };
+        Lvm::run_quiet(&format!(
+            "pvcreate --yes --zero y --wipesignatures {wipe} {}",
+            device_arg(device)?
+        ))
+    }
+
+    /// # Allow or forbid allocating extents on a PV
+    /// Checks that the device is a PV with `pvs <pv>`, then runs `pvchange --allocatable <y|n> <pv>`.
+    /// Extents already allocated on the PV stay, e.g. forbid allocation before moving them off, see [Lvm::pv_drain].
+    pub fn pv_allocatable(pv: &Path, allocatable: bool) -> Result<(), CommandRetCode> {
+        known_pv(pv)?;
+        Lvm::run_quiet(&pv_allocatable_command(pv, allocatable)?)
+    }
+
+    /// # Whether new extents can be allocated on a PV
+    /// Runs `pvs <pv>`, see [PvInfo::is_allocatable]
+    pub fn pv_is_allocatable(pv: &Path) -> Result<bool, CommandRetCode> {
+        Ok(known_pv(pv)?.is_allocatable())
+    }
+
+    /// # Move all data off a PV
+    /// Forbids allocation on the PV (see [Lvm::pv_allocatable]) so nothing is allocated there meanwhile,
+    /// then moves its extents to other PVs of the VG with `pvmove <pv>`, see [PvManager::move_extents].
+    /// The PV stays non-allocatable afterwards, e.g. to remove it from the VG.
+    ///
+    /// `pvmove` fails with [CommandRetCode::ProcessingFailed] if there's no data on the PV.
+    pub fn pv_drain(pv: &Path) -> Result<(), CommandRetCode> {
+        Self::pv_allocatable(pv, false)?;
+        Self::pv().move_extents(pv, None)
+    }
+}
+
+/// # `pvchange --allocatable` command
+/// See [Lvm::pv_allocatable]
+pub(crate) fn pv_allocatable_command(
+    pv: &Path,
+    allocatable: bool,
+) -> Result<String, CommandRetCode> {
+    let allocatable = if allocatable { "y" } else { "n" };
+    Ok(format!(
+        "pvchange --allocatable {allocatable} {}",
+        device_arg(pv)?
+    ))
+}
+
+/// The PV on the device, LVM fails if the device isn't a PV
+fn known_pv(pv: &Path) -> Result<PvInfo, CommandRetCode> {
+    let command = format!("pvs {}", device_arg(pv)?);
+    pvs_from(&command)?
+        .into_iter()
+        .next()
+        .ok_or(CommandRetCode::UnexpectedOutput(command))
+}
+
+/// # Progress of [Lvm::pvmove_async]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PvMoveProgress {
+    pub moved_extents: u64,
+    /// Extents allocated on the source PV when the move started
+    pub total_extents: u64,
+}
+
+/// # Running `pvmove`
+/// Obtained with [Lvm::pvmove_async]
+#[derive(Debug)]
+pub struct PvMoveHandle {
+    src: String,
+    aborted: Arc<AtomicBool>,
+    thread: JoinHandle<Result<(), CommandRetCode>>,
+}
+
+impl PvMoveHandle {
+    /// # Block until the move completes or is aborted
+    /// Returns the first error of the progress polling, [CommandRetCode::ClosurePanicked] if the progress callback panicked.
+    pub fn wait(self) -> Result<(), CommandRetCode> {
+        self.thread
+            .join()
+            .map_err(|payload| CommandRetCode::ClosurePanicked(panic_message(payload)))?
+    }
+
+    /// # Stop the move
+    /// Runs `pvmove --abort <src>`, extents moved so far stay on the destination.
+    /// The progress callback isn't called after that, [PvMoveHandle::wait] returns once polling stops.
+    pub fn abort(&self) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!("pvmove --abort {}", self.src))?;
+        self.aborted.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+impl PvManager {
+    /// `pvcreate <device>`, with the `nix` feature the device has to be a block device, see [Lvm::validate_block_device]
+    pub fn create(&self, device: &Path) -> Result<(), CommandRetCode> {
+        #[cfg(feature = "nix")]
+        Lvm::validate_block_device(device)?;
+        Lvm::run_quiet(&format!("pvcreate {}", device_arg(device)?))
+    }
+
+    /// `pvremove <device>`
+    pub fn remove(&self, device: &Path) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!("pvremove {}", device_arg(device)?))
+    }
+
+    /// `pvresize <device>`, `new_size` overrides the size LVM detects
+    pub fn resize(&self, device: &Path, new_size: Option<ByteSize>) -> Result<(), CommandRetCode> {
+        let device = device_arg(device)?;
+        match new_size {
+            Some(ByteSize(0)) => Err(CommandRetCode::InvalidParameters),
+            Some(ByteSize(bytes)) => Lvm::run_quiet(&format!(
+                "pvresize --setphysicalvolumesize {bytes}b {device}"
+            )),
+            None => Lvm::run_quiet(&format!("pvresize {device}")),
+        }
+    }
+
+    /// `pvmove <src> [dst]` - moves allocated extents off `src`, to any free space of the VG if `dst` is not set
+    pub fn move_extents(&self, src: &Path, dst: Option<&Path>) -> Result<(), CommandRetCode> {
+        let src = device_arg(src)?;
+        match dst {
+            Some(dst) => Lvm::run_quiet(&format!("pvmove {src} {}", device_arg(dst)?)),
+            None => Lvm::run_quiet(&format!("pvmove {src}")),
+        }
+    }
+}
+
+/// # Check that device exists and could be put into a command line
+/// lvm2cmd splits command line by whitespaces, so such paths aren't supported
+pub(crate) fn device_arg(device: &Path) -> Result<&str, CommandRetCode> {
+    match device.to_str() {
+        Some(s) if device.exists() && !s.contains(char::is_whitespace) => Ok(s),
+        _ => Err(CommandRetCode::InvalidParameters),
+    }
+}
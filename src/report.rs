@@ -0,0 +1,846 @@
+//! Typed representation of `pvs`, `vgs` and `lvs` JSON reports.
+//! Structs mirror the default report columns (see `man 8 lvmreport`), extra columns requested via `-o` are optional.
+
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fmt::Display,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, de};
+use serde_json::Value;
+
+use crate::{
+    lv::{lv_path_arg, lv_validate_name, quote},
+    lvm::{CommandRetCode, Lvm},
+    units::ByteSize,
+    vg::{validate_vg_name, vg_validate_name},
+};
+
+/// Top-level object of the `--reportformat json` output
+#[derive(Debug, Clone, Deserialize)]
+pub struct Report<T> {
+    pub report: Vec<T>,
+}
+
+/// One `pvs` report
+#[derive(Debug, Clone, Deserialize)]
+pub struct PvReport {
+    pub pv: Vec<PvInfo>,
+}
+
+/// One `vgs` report
+#[derive(Debug, Clone, Deserialize)]
+pub struct VgReport {
+    pub vg: Vec<VgInfo>,
+}
+
+/// One `lvs` report
+#[derive(Debug, Clone, Deserialize)]
+pub struct LvReport {
+    pub lv: Vec<LvInfo>,
+}
+
+/// Physical volume as reported by `pvs`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PvInfo {
+    pub pv_name: String,
+    /// None for PVs which don't belong to any VG
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub vg_name: Option<String>,
+    pub pv_fmt: String,
+    pub pv_attr: String,
+    pub pv_size: ByteSize,
+    pub pv_free: ByteSize,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub pv_uuid: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub dev_size: Option<ByteSize>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub pv_used: Option<ByteSize>,
+}
+
+impl PvInfo {
+    /// # Whether the PV is missing
+    /// The 3rd character of `pv_attr` is `m` for PVs LVM knows from VG metadata, but can't find the device of.
+    /// Such PVs are only reported in partial mode, see [Lvm::pvs_with_missing]
+    pub fn is_missing(&self) -> bool {
+        self.pv_attr.chars().nth(2) == Some('m')
+    }
+
+    /// # Whether new extents can be allocated on the PV
+    /// The 1st character of `pv_attr` is `a` for allocatable PVs, see [Lvm::pv_allocatable]
+    pub fn is_allocatable(&self) -> bool {
+        self.pv_attr.starts_with('a')
+    }
+}
+
+/// # PV along with its free space, see [Lvm::pvs_free]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PvFreeSpace {
+    pub pv: PvInfo,
+    pub free: ByteSize,
+}
+
+/// Volume group as reported by `vgs`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct VgInfo {
+    pub vg_name: String,
+    #[serde(deserialize_with = "from_string")]
+    pub pv_count: u64,
+    #[serde(deserialize_with = "from_string")]
+    pub lv_count: u64,
+    #[serde(deserialize_with = "from_string")]
+    pub snap_count: u64,
+    pub vg_attr: String,
+    pub vg_size: ByteSize,
+    pub vg_free: ByteSize,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub vg_uuid: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub vg_extent_size: Option<ByteSize>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub vg_extent_count: Option<u64>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub vg_free_count: Option<u64>,
+}
+
+/// Logical volume as reported by `lvs`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LvInfo {
+    pub lv_name: String,
+    /// `fullreport` doesn't have it in LV section, it's taken from the VG then
+    #[serde(default)]
+    pub vg_name: String,
+    pub lv_attr: String,
+    pub lv_size: ByteSize,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub pool_lv: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub origin: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub data_percent: Option<f64>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub metadata_percent: Option<f64>,
+    /// Only with `-o +snap_percent`
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub snap_percent: Option<f64>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub move_pv: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub mirror_log: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub copy_percent: Option<f64>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub convert_lv: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub lv_uuid: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub lv_path: Option<String>,
+}
+
+impl LvInfo {
+    /// # Whether the LV is active
+    /// The 5th character of `lv_attr` is the state, `a` stands for active
+    pub fn is_active(&self) -> bool {
+        self.lv_attr.chars().nth(4) == Some('a')
+    }
+
+    /// # Whether the LV is open
+    /// The 6th character of `lv_attr` is `o` if the device is in use, e.g. mounted
+    pub fn is_open(&self) -> bool {
+        self.lv_attr.chars().nth(5) == Some('o')
+    }
+
+    /// # Whether newly provisioned blocks are zeroed
+    /// The 8th character of `lv_attr` is `z` for thin pools zeroing blocks before use
+    pub fn is_zeroed(&self) -> bool {
+        self.lv_attr.chars().nth(7) == Some('z')
+    }
+}
+
+/// # Device path of an LV
+/// `/dev/<vg>/<lv>`, the symlink udev creates for active LVs, e.g. to pass to [std::fs] functions.
+///
+/// ```
+/// use lvm_sys2::report::DiskPath;
+/// let path = DiskPath::try_from("/dev/vg0/data").unwrap();
+/// assert_eq!(path.to_string(), "/dev/vg0/data");
+/// assert!(DiskPath::try_from("/dev/sdb").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiskPath(PathBuf);
+
+impl From<&LvInfo> for DiskPath {
+    fn from(lv: &LvInfo) -> Self {
+        Self(PathBuf::from(format!("/dev/{}/{}", lv.vg_name, lv.lv_name)))
+    }
+}
+
+/// Returns [CommandRetCode::InvalidParameters] if the path isn't `/dev/<vg>/<lv>` with valid names
+impl TryFrom<&str> for DiskPath {
+    type Error = CommandRetCode;
+
+    fn try_from(path: &str) -> Result<Self, Self::Error> {
+        let (vg, lv) = path
+            .strip_prefix("/dev/")
+            .and_then(|rest| rest.split_once('/'))
+            .ok_or(CommandRetCode::InvalidParameters)?;
+        vg_validate_name(vg)?;
+        lv_validate_name(lv)?;
+        Ok(Self(PathBuf::from(path)))
+    }
+}
+
+impl AsRef<Path> for DiskPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl From<DiskPath> for PathBuf {
+    fn from(path: DiskPath) -> Self {
+        path.0
+    }
+}
+
+impl Display for DiskPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+
+/// # Segment of a logical volume as reported by `lvs --segments`
+/// Segments map ranges of LV extents to devices, see [Lvm::segment_info]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SegmentInfo {
+    /// Offset of the segment in the LV
+    pub seg_start: ByteSize,
+    pub seg_size: ByteSize,
+    /// e.g. `linear`, `striped`, `raid1` or `thin`
+    #[serde(rename = "segtype")]
+    pub seg_type: String,
+    /// Empty for segments without devices, e.g. thin ones
+    #[serde(deserialize_with = "devices_list")]
+    pub devices: Vec<SegmentDevice>,
+}
+
+/// # Segment of a physical volume as reported by `pvs --segments`
+/// Shows which LV uses a range of PV extents, see [Lvm::pvsegs]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PvSegInfo {
+    /// First extent of the segment
+    #[serde(deserialize_with = "from_string")]
+    pub pvseg_start: u64,
+    /// In extents
+    #[serde(deserialize_with = "from_string")]
+    pub pvseg_size: u64,
+    /// None for free extents
+    #[serde(deserialize_with = "empty_as_none")]
+    pub lv_name: Option<String>,
+    /// `free` for free extents
+    #[serde(rename = "segtype")]
+    pub seg_type: String,
+}
+
+/// # Device of a segment and the first extent used on it
+/// LVM prints it as `/dev/sdb(0)`, striped and RAID segments have several ones.
+/// Sub-LVs, e.g. `lv_rimage_0`, come instead of PVs for RAID and mirrors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentDevice {
+    pub device: String,
+    pub start_extent: u64,
+}
+
+impl FromStr for SegmentDevice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format!("invalid segment device: {s:?}");
+        let (device, extent) = s.trim().split_once('(').ok_or_else(err)?;
+        let start_extent = extent
+            .strip_suffix(')')
+            .and_then(|e| e.parse().ok())
+            .ok_or_else(err)?;
+        Ok(Self {
+            device: device.to_string(),
+            start_extent,
+        })
+    }
+}
+
+/// # Combined `fullreport` output
+/// LVM reports each VG separately with its PVs and LVs, here they are merged together
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "Report<FullReportEntry>")]
+pub struct FullReport {
+    pub pv: Vec<PvInfo>,
+    pub vg: Vec<VgInfo>,
+    pub lv: Vec<LvInfo>,
+}
+
+/// One VG section of `fullreport`
+#[derive(Deserialize)]
+struct FullReportEntry {
+    #[serde(default)]
+    pv: Vec<PvInfo>,
+    #[serde(default)]
+    vg: Vec<VgInfo>,
+    #[serde(default)]
+    lv: Vec<LvInfo>,
+}
+
+impl From<Report<FullReportEntry>> for FullReport {
+    fn from(value: Report<FullReportEntry>) -> Self {
+        let mut full = Self {
+            pv: vec![],
+            vg: vec![],
+            lv: vec![],
+        };
+        for mut entry in value.report {
+            // PV / LV sections don't have VG fields
+            if let [vg] = entry.vg.as_slice() {
+                for pv in entry.pv.iter_mut().filter(|pv| pv.vg_name.is_none()) {
+                    pv.vg_name = Some(vg.vg_name.clone());
+                }
+                for lv in entry.lv.iter_mut().filter(|lv| lv.vg_name.is_empty()) {
+                    lv.vg_name = vg.vg_name.clone();
+                }
+            }
+            full.pv.append(&mut entry.pv);
+            full.vg.append(&mut entry.vg);
+            full.lv.append(&mut entry.lv);
+        }
+        full
+    }
+}
+
+/// # Physical volume as reported by `pvdisplay`
+/// Has metadata area and extent details which [PvInfo] lacks
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PvDetail {
+    pub pv_name: String,
+    /// None for PVs which don't belong to any VG
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub vg_name: Option<String>,
+    pub pv_uuid: String,
+    pub pv_fmt: String,
+    pub pv_attr: String,
+    pub pv_size: ByteSize,
+    pub pv_free: ByteSize,
+    pub pv_used: ByteSize,
+    pub dev_size: ByteSize,
+    /// Offset of the first extent
+    pub pe_start: ByteSize,
+    #[serde(deserialize_with = "from_string")]
+    pub pv_pe_count: u64,
+    #[serde(deserialize_with = "from_string")]
+    pub pv_pe_alloc_count: u64,
+    #[serde(deserialize_with = "from_string")]
+    pub pv_mda_count: u64,
+    #[serde(deserialize_with = "from_string")]
+    pub pv_mda_used_count: u64,
+    pub pv_mda_size: ByteSize,
+    pub pv_mda_free: ByteSize,
+}
+
+/// # Volume group as reported by `vgdisplay`
+/// Has limits, sequence number and metadata area details which [VgInfo] lacks
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct VgDetail {
+    pub vg_name: String,
+    pub vg_uuid: String,
+    pub vg_fmt: String,
+    pub vg_attr: String,
+    pub vg_size: ByteSize,
+    pub vg_free: ByteSize,
+    pub vg_extent_size: ByteSize,
+    #[serde(deserialize_with = "from_string")]
+    pub vg_extent_count: u64,
+    #[serde(deserialize_with = "from_string")]
+    pub vg_free_count: u64,
+    /// 0 means unlimited
+    #[serde(deserialize_with = "from_string")]
+    pub max_lv: u64,
+    /// 0 means unlimited
+    #[serde(deserialize_with = "from_string")]
+    pub max_pv: u64,
+    #[serde(deserialize_with = "from_string")]
+    pub pv_count: u64,
+    #[serde(deserialize_with = "from_string")]
+    pub lv_count: u64,
+    #[serde(deserialize_with = "from_string")]
+    pub snap_count: u64,
+    /// Incremented on every metadata change
+    #[serde(deserialize_with = "from_string")]
+    pub vg_seqno: u64,
+    #[serde(deserialize_with = "from_string")]
+    pub vg_mda_count: u64,
+    #[serde(deserialize_with = "from_string")]
+    pub vg_mda_used_count: u64,
+    /// Number of metadata copies or `unmanaged`
+    pub vg_mda_copies: String,
+}
+
+/// # Logical volume as reported by `lvdisplay`
+/// Has layout, activation and creation details which [LvInfo] lacks
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LvDetail {
+    pub lv_name: String,
+    pub vg_name: String,
+    pub lv_uuid: String,
+    pub lv_path: String,
+    pub lv_attr: String,
+    pub lv_size: ByteSize,
+    /// e.g. `linear` or `thin,sparse`
+    pub lv_layout: String,
+    /// e.g. `public` or `private,thin,pool,data`
+    pub lv_role: String,
+    pub lv_active: String,
+    #[serde(deserialize_with = "from_string")]
+    pub seg_count: u64,
+    /// Device major number, -1 if the LV isn't active
+    #[serde(deserialize_with = "from_string")]
+    pub lv_kernel_major: i64,
+    /// Device minor number, -1 if the LV isn't active
+    #[serde(deserialize_with = "from_string")]
+    pub lv_kernel_minor: i64,
+    /// Creation time as LVM prints it, e.g. `2024-01-01 12:00:00 +0000`
+    pub lv_time: String,
+    pub lv_host: String,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub pool_lv: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub origin: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub data_percent: Option<f64>,
+}
+
+/// Columns of [PvDetail], order doesn't matter in JSON
+const PV_DETAIL_FIELDS: &str = "pv_name,vg_name,pv_uuid,pv_fmt,pv_attr,pv_size,pv_free,pv_used,dev_size,pe_start,pv_pe_count,pv_pe_alloc_count,pv_mda_count,pv_mda_used_count,pv_mda_size,pv_mda_free";
+/// Columns of [VgDetail]
+const VG_DETAIL_FIELDS: &str = "vg_name,vg_uuid,vg_fmt,vg_attr,vg_size,vg_free,vg_extent_size,vg_extent_count,vg_free_count,max_lv,max_pv,pv_count,lv_count,snap_count,vg_seqno,vg_mda_count,vg_mda_used_count,vg_mda_copies";
+/// Columns of [LvDetail]
+const LV_DETAIL_FIELDS: &str = "lv_name,vg_name,lv_uuid,lv_path,lv_attr,lv_size,lv_layout,lv_role,lv_active,seg_count,lv_kernel_major,lv_kernel_minor,lv_time,lv_host,pool_lv,origin,data_percent";
+
+#[derive(Deserialize)]
+struct SegReport {
+    seg: Vec<SegmentInfo>,
+}
+
+#[derive(Deserialize)]
+struct PvSegReport {
+    pvseg: Vec<PvSegInfo>,
+}
+
+/// Columns of [PvSegInfo]
+const PVSEG_FIELDS: &str = "pvseg_start,pvseg_size,lv_name,segtype";
+
+/// Columns of [SegmentInfo]
+const SEGMENT_FIELDS: &str = "seg_start,seg_size,segtype,devices";
+
+#[derive(Deserialize)]
+struct PvDetailReport {
+    pv: Vec<PvDetail>,
+}
+
+#[derive(Deserialize)]
+struct VgDetailReport {
+    vg: Vec<VgDetail>,
+}
+
+#[derive(Deserialize)]
+struct LvDetailReport {
+    lv: Vec<LvDetail>,
+}
+
+/// `vgs -o <size field>` report
+#[derive(Deserialize)]
+struct VgSizeReport {
+    vg: Vec<HashMap<String, ByteSize>>,
+}
+
+/// Characters not allowed in `--select` filters: shell metacharacters and the quote the filter is wrapped into
+const FORBIDDEN_SELECT_CHARS: &[char] = &[';', '|', '&', '`', '$', '\''];
+
+impl Lvm {
+    /// # List physical volumes
+    /// Runs `pvs` and deserializes all reports into [PvInfo]
+    pub fn pvs() -> Result<Vec<PvInfo>, CommandRetCode> {
+        pvs_from("pvs")
+    }
+
+    /// # List volume groups
+    /// Runs `vgs` and deserializes all reports into [VgInfo]
+    pub fn vgs() -> Result<Vec<VgInfo>, CommandRetCode> {
+        vgs_from("vgs")
+    }
+
+    /// # List logical volumes
+    /// Runs `lvs` and deserializes all reports into [LvInfo]
+    pub fn lvs() -> Result<Vec<LvInfo>, CommandRetCode> {
+        lvs_from("lvs")
+    }
+
+    /// # List physical volumes of a volume group
+    /// `pvs` takes PV names only, so it runs `pvs --select 'vg_name=<vg>'`.
+    /// An unknown VG results into an empty [Vec] as well as a VG without PVs.
+    pub fn pvs_by_vg(vg: &str) -> Result<Vec<PvInfo>, CommandRetCode> {
+        pvs_from(&format!(
+            "pvs {}",
+            select_arg(&format!("vg_name={}", validate_vg_name(vg)?))?
+        ))
+    }
+
+    /// # List physical volumes by free space
+    /// Runs `pvs` and sorts PVs by [PvInfo::pv_free], the largest first, e.g. to pick PVs for a new LV.
+    /// Full PVs are included with zero free space.
+    pub fn pvs_free() -> Result<Vec<PvFreeSpace>, CommandRetCode> {
+        let mut pvs: Vec<_> = Self::pvs()?
+            .into_iter()
+            .map(|pv| PvFreeSpace {
+                free: pv.pv_free,
+                pv,
+            })
+            .collect();
+        pvs.sort_by_key(|p| Reverse(p.free));
+        Ok(pvs)
+    }
+
+    /// # List logical volumes of a volume group
+    /// Runs `lvs '<vg>'`, LVM fails if there's no such VG
+    pub fn lvs_by_vg(vg: &str) -> Result<Vec<LvInfo>, CommandRetCode> {
+        lvs_from(&format!("lvs {}", quote(validate_vg_name(vg)?)))
+    }
+
+    /// # Free space of a volume group
+    /// Runs `vgs -o vg_free '<vg>'`, which is cheaper than [Lvm::vgs] for monitoring a single VG
+    pub fn vg_free_space(vg: &str) -> Result<ByteSize, CommandRetCode> {
+        vg_size_field(vg, "vg_free")
+    }
+
+    /// # Size of a volume group
+    /// Runs `vgs -o vg_size '<vg>'`, see [Lvm::vg_free_space]
+    pub fn vg_size(vg: &str) -> Result<ByteSize, CommandRetCode> {
+        vg_size_field(vg, "vg_size")
+    }
+
+    /// # Extent size of a volume group
+    /// Runs `vgs -o vg_extent_size '<vg>'`, see [Lvm::vg_free_space]
+    pub fn vg_extent_size(vg: &str) -> Result<ByteSize, CommandRetCode> {
+        vg_size_field(vg, "vg_extent_size")
+    }
+
+    /// # List logical volumes of all VGs
+    /// Same as [Lvm::lvs], but collects [Lvm::lvs_all_vgs_iter]
+    pub fn lvs_all_vgs() -> Result<Vec<LvInfo>, CommandRetCode> {
+        Self::lvs_all_vgs_iter().collect()
+    }
+
+    /// # Iterate over logical volumes of all VGs
+    /// Runs `lvs` and deserializes LVs one by one with [serde_json::StreamDeserializer],
+    /// so neither a [Value] tree nor a [Vec] of the whole report is built. Handy on systems with lots of LVs.
+    ///
+    /// A failed command or malformed entry is yielded as the last item.
+    pub fn lvs_all_vgs_iter() -> impl Iterator<Item = Result<LvInfo, CommandRetCode>> {
+        ReportIter::new(Lvm::run_raw("lvs"), "lv")
+    }
+
+    /// # List physical volumes in read-only mode
+    /// Same as [Lvm::pvs], see [Lvm::run_readonly] for the caveats
+    pub fn pvs_readonly() -> Result<Vec<PvInfo>, CommandRetCode> {
+        pvs_from("pvs --readonly")
+    }
+
+    /// # List volume groups in read-only mode
+    /// See [Lvm::pvs_readonly]
+    pub fn vgs_readonly() -> Result<Vec<VgInfo>, CommandRetCode> {
+        vgs_from("vgs --readonly")
+    }
+
+    /// # List logical volumes in read-only mode
+    /// See [Lvm::pvs_readonly]
+    pub fn lvs_readonly() -> Result<Vec<LvInfo>, CommandRetCode> {
+        lvs_from("lvs --readonly")
+    }
+
+    /// # Get all PVs, VGs and LVs at once
+    /// Runs `fullreport`, which is cheaper than separate `pvs`, `vgs` and `lvs` and gives a consistent snapshot
+    pub fn fullreport() -> Result<FullReport, CommandRetCode> {
+        parse_report(Value::Object(Lvm::run("fullreport")?))
+    }
+
+    /// # List physical volumes matching the filter
+    /// Runs `pvs --select '<filter>'`, e.g. `pv_size > 10g`.
+    /// See "Selection" in [lvmreport(7)](https://man7.org/linux/man-pages/man7/lvmreport.7.html) for the syntax.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if the filter contains any of `;|&$'` or backtick.
+    /// No matches result into an empty [Vec].
+    pub fn pvs_select(filter: &str) -> Result<Vec<PvInfo>, CommandRetCode> {
+        pvs_from(&format!("pvs {}", select_arg(filter)?))
+    }
+
+    /// # List volume groups matching the filter
+    /// See [Lvm::pvs_select]
+    pub fn vgs_select(filter: &str) -> Result<Vec<VgInfo>, CommandRetCode> {
+        vgs_from(&format!("vgs {}", select_arg(filter)?))
+    }
+
+    /// # List logical volumes matching the filter
+    /// See [Lvm::pvs_select]
+    pub fn lvs_select(filter: &str) -> Result<Vec<LvInfo>, CommandRetCode> {
+        lvs_from(&format!("lvs {}", select_arg(filter)?))
+    }
+
+    /// # Detailed info about a physical volume
+    /// Runs `pvdisplay -C` with the columns of [PvDetail].
+    /// The device isn't checked on the filesystem, a missing one results into LVM's error.
+    pub fn pvdisplay(device: &Path) -> Result<PvDetail, CommandRetCode> {
+        let command = format!(
+            "pvdisplay -C -o {PV_DETAIL_FIELDS} {}",
+            pv_name_arg(device)?
+        );
+        let reports: Report<PvDetailReport> = parse_report(Value::Object(Lvm::run(&command)?))?;
+        single(reports.report.into_iter().flat_map(|r| r.pv), command)
+    }
+
+    /// # Segments of a physical volume
+    /// Runs `pvs --segments` with the columns of [PvSegInfo], segments come in PV order, free ones included.
+    /// The device isn't checked on the filesystem, see [Lvm::pvdisplay].
+    pub fn pvsegs(pv: &Path) -> Result<Vec<PvSegInfo>, CommandRetCode> {
+        let command = format!("pvs --segments -o {PVSEG_FIELDS} {}", pv_name_arg(pv)?);
+        let reports: Report<PvSegReport> = parse_report(Value::Object(Lvm::run(&command)?))?;
+        Ok(reports.report.into_iter().flat_map(|r| r.pvseg).collect())
+    }
+
+    /// # Detailed info about a volume group
+    /// Runs `vgdisplay -C` with the columns of [VgDetail]
+    pub fn vgdisplay(vg: &str) -> Result<VgDetail, CommandRetCode> {
+        let command = format!(
+            "vgdisplay -C -o {VG_DETAIL_FIELDS} {}",
+            quote(validate_vg_name(vg)?)
+        );
+        let reports: Report<VgDetailReport> = parse_report(Value::Object(Lvm::run(&command)?))?;
+        single(reports.report.into_iter().flat_map(|r| r.vg), command)
+    }
+
+    /// # Segments of a logical volume
+    /// Runs `lvs -a --segments` with the columns of [SegmentInfo] for `VG/LV`, segments come in LV order.
+    /// Sub-LVs, e.g. RAID images, aren't expanded, query them by their names to see their devices.
+    pub fn segment_info(vg: &str, lv: &str) -> Result<Vec<SegmentInfo>, CommandRetCode> {
+        let command = format!(
+            "lvs -a --segments -o {SEGMENT_FIELDS} {}",
+            lv_path_arg(vg, lv)?
+        );
+        let reports: Report<SegReport> = parse_report(Value::Object(Lvm::run(&command)?))?;
+        Ok(reports.report.into_iter().flat_map(|r| r.seg).collect())
+    }
+
+    /// # Detailed info about a logical volume
+    /// Runs `lvdisplay -C` with the columns of [LvDetail]
+    pub fn lvdisplay(vg: &str, lv: &str) -> Result<LvDetail, CommandRetCode> {
+        let command = format!(
+            "lvdisplay -C -o {LV_DETAIL_FIELDS} {}",
+            lv_path_arg(vg, lv)?
+        );
+        let reports: Report<LvDetailReport> = parse_report(Value::Object(Lvm::run(&command)?))?;
+        single(reports.report.into_iter().flat_map(|r| r.lv), command)
+    }
+}
+
+/// # Lazy iterator over entries of `"<key>": [...]` arrays of all reports
+/// Only looks for the arrays, the rest of the output is skipped
+struct ReportIter<T> {
+    data: String,
+    pos: usize,
+    /// `"lv"` etc. with quotes
+    key: String,
+    in_array: bool,
+    /// error to yield before stopping
+    failure: Option<CommandRetCode>,
+    done: bool,
+    _entry: PhantomData<T>,
+}
+
+impl<T> ReportIter<T> {
+    fn new(output: Result<String, CommandRetCode>, key: &str) -> Self {
+        let (data, failure) = match output {
+            Ok(data) => (data, None),
+            Err(e) => (String::new(), Some(e)),
+        };
+        Self {
+            data,
+            pos: 0,
+            key: format!("\"{key}\""),
+            in_array: false,
+            failure,
+            done: false,
+            _entry: PhantomData,
+        }
+    }
+
+    /// move past whitespace, then past `c` if it's next
+    fn skip(&mut self, c: char) -> bool {
+        let rest = self.data[self.pos..].trim_start();
+        self.pos = self.data.len() - rest.len();
+        if rest.starts_with(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// move into the next array of the key, false if there are no more
+    fn enter_array(&mut self) -> bool {
+        while let Some(found) = self.data[self.pos..].find(&self.key) {
+            self.pos += found + self.key.len();
+            if self.skip(':') && self.skip('[') {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn fail(&mut self, e: CommandRetCode) -> Option<Result<T, CommandRetCode>> {
+        self.done = true;
+        Some(Err(e))
+    }
+}
+
+impl<T: for<'de> Deserialize<'de>> Iterator for ReportIter<T> {
+    type Item = Result<T, CommandRetCode>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.failure.take() {
+            return self.fail(e);
+        }
+        while !self.done {
+            if !self.in_array {
+                self.in_array = self.enter_array();
+                self.done = !self.in_array;
+                continue;
+            }
+            if self.skip(']') {
+                self.in_array = false;
+                continue;
+            }
+            // entries after the first one are separated with a comma
+            self.skip(',');
+            let mut stream =
+                serde_json::Deserializer::from_str(&self.data[self.pos..]).into_iter::<T>();
+            return match stream.next() {
+                Some(Ok(entry)) => {
+                    self.pos += stream.byte_offset();
+                    Some(Ok(entry))
+                }
+                Some(Err(e)) => {
+                    let data = self.data[self.pos..].to_string();
+                    self.fail(CommandRetCode::JsonDeserializationFailed((e, data)))
+                }
+                None => self.fail(CommandRetCode::UnexpectedOutput(self.data.clone())),
+            };
+        }
+        None
+    }
+}
+
+/// The only entry of a report about a single object
+fn single<T>(mut entries: impl Iterator<Item = T>, command: String) -> Result<T, CommandRetCode> {
+    entries
+        .next()
+        .ok_or(CommandRetCode::UnexpectedOutput(command))
+}
+
+/// The size field of a single VG, LVM fails if there's no such VG
+fn vg_size_field(vg: &str, field: &str) -> Result<ByteSize, CommandRetCode> {
+    let command = format!("vgs -o {field} {}", quote(validate_vg_name(vg)?));
+    let reports: Report<VgSizeReport> = parse_report(Value::Object(Lvm::run(&command)?))?;
+    let sizes = reports.report.into_iter().flat_map(|r| r.vg);
+    single(sizes.filter_map(|mut row| row.remove(field)), command)
+}
+
+/// Quoted PV name, LVM resolves it, so it isn't checked on the filesystem
+fn pv_name_arg(device: &Path) -> Result<String, CommandRetCode> {
+    match device.to_str() {
+        Some(s) if !s.is_empty() && !s.contains(|c: char| c.is_whitespace() || c == '\'') => {
+            Ok(quote(s))
+        }
+        _ => Err(CommandRetCode::InvalidParameters),
+    }
+}
+
+/// Run `pvs`-like command and collect PVs from all reports
+pub(crate) fn pvs_from(command: &str) -> Result<Vec<PvInfo>, CommandRetCode> {
+    let reports: Report<PvReport> = parse_report(Value::Object(Lvm::run(command)?))?;
+    Ok(reports.report.into_iter().flat_map(|r| r.pv).collect())
+}
+
+/// Run `vgs`-like command and collect VGs from all reports
+pub(crate) fn vgs_from(command: &str) -> Result<Vec<VgInfo>, CommandRetCode> {
+    let reports: Report<VgReport> = parse_report(Value::Object(Lvm::run(command)?))?;
+    Ok(reports.report.into_iter().flat_map(|r| r.vg).collect())
+}
+
+/// Run `lvs`-like command and collect LVs from all reports
+pub(crate) fn lvs_from(command: &str) -> Result<Vec<LvInfo>, CommandRetCode> {
+    let reports: Report<LvReport> = parse_report(Value::Object(Lvm::run(command)?))?;
+    Ok(reports.report.into_iter().flat_map(|r| r.lv).collect())
+}
+
+/// Quoted `--select` argument
+pub(crate) fn select_arg(filter: &str) -> Result<String, CommandRetCode> {
+    if filter.trim().is_empty() || filter.contains(FORBIDDEN_SELECT_CHARS) {
+        return Err(CommandRetCode::InvalidParameters);
+    }
+    Ok(format!("--select '{filter}'"))
+}
+
+/// Deserialize already parsed command output into a typed report
+pub(crate) fn parse_report<T: for<'de> Deserialize<'de>>(
+    value: Value,
+) -> Result<T, CommandRetCode> {
+    T::deserialize(&value)
+        .map_err(|e| CommandRetCode::JsonDeserializationFailed((e, value.to_string())))
+}
+
+/// LVM reports numbers as strings, e.g. `"pv_count":"1"`
+fn from_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    String::deserialize(deserializer)?
+        .trim()
+        .parse()
+        .map_err(de::Error::custom)
+}
+
+/// LVM reports absent values as empty strings, e.g. `"origin":""`
+fn empty_as_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    match String::deserialize(deserializer)?.trim() {
+        "" => Ok(None),
+        s => s.parse().map(Some).map_err(de::Error::custom),
+    }
+}
+
+/// LVM reports segment devices comma-separated, e.g. `"devices":"/dev/sdb(0),/dev/sdc(0)"`
+fn devices_list<'de, D>(deserializer: D) -> Result<Vec<SegmentDevice>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    String::deserialize(deserializer)?
+        .split(',')
+        .filter(|d| !d.trim().is_empty())
+        .map(|d| d.parse().map_err(de::Error::custom))
+        .collect()
+}
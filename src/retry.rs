@@ -0,0 +1,139 @@
+//! Retries of LVM commands failed due to transient reasons, e.g. lock contention on busy systems.
+
+use std::{error::Error, fmt::Display, thread, time::Duration};
+
+use crate::lvm::{CommandRetCode, Lvm};
+
+/// Longest delay between attempts of [Lvm::run_with_retry], the backoff stops growing there
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// # How to retry a command
+/// Delay before the N-th retry is `initial_delay * backoff_factor ^ (N - 1)`, up to [MAX_RETRY_DELAY]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub backoff_factor: f64,
+}
+
+impl Default for RetryPolicy {
+    /// 3 attempts, 100ms initial delay, doubled each retry
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(100),
+            backoff_factor: 2.0,
+        }
+    }
+}
+
+impl Lvm {
+    /// # Run LVM command retrying on [CommandRetCode::ProcessingFailed]
+    /// Same as [Lvm::run], but sleeps and retries according to the `policy` if LVM fails to process the command.
+    /// All other errors are returned immediately.
+    /// Returns [CommandRetCode::RetriesExhausted] with the last error if no attempt succeeded,
+    /// [CommandRetCode::InvalidParameters] if `backoff_factor` is negative, NaN or infinite.
+    pub fn run_with_retry(
+        command: &str,
+        policy: RetryPolicy,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, CommandRetCode> {
+        if !policy.backoff_factor.is_finite() || policy.backoff_factor < 0.0 {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        let mut delay = policy.initial_delay.min(MAX_RETRY_DELAY);
+        let mut attempt = 1;
+        loop {
+            match Self::run(command) {
+                Err(CommandRetCode::ProcessingFailed) if attempt < policy.max_attempts => {
+                    thread::sleep(delay);
+                    // the product may still overflow Duration
+                    delay =
+                        Duration::try_from_secs_f64(delay.as_secs_f64() * policy.backoff_factor)
+                            .map_or(MAX_RETRY_DELAY, |next| next.min(MAX_RETRY_DELAY));
+                    attempt += 1;
+                }
+                Err(CommandRetCode::ProcessingFailed) => {
+                    return Err(CommandRetCode::RetriesExhausted(Box::new(
+                        CommandRetCode::ProcessingFailed,
+                    )));
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
+/// # Error worth retrying
+/// Holds a [CommandRetCode] which [CommandRetCode::is_retryable], so retry loops can tell it apart with types:
+///
+/// ```
+/// use lvm_sys2::{lvm::CommandRetCode, retry::RecoverableError};
+///
+/// fn attempt() -> Result<(), CommandRetCode> {
+///     Err(CommandRetCode::ProcessingFailed)
+/// }
+///
+/// fn step() -> Result<Option<RecoverableError>, CommandRetCode> {
+///     match attempt() {
+///         Ok(()) => Ok(None),
+///         // permanent errors bubble up with `?`
+///         Err(e) => Ok(Some(RecoverableError::try_from(e)?)),
+///     }
+/// }
+/// assert!(step().unwrap().is_some());
+/// ```
+///
+/// [From] can't be partial, so the conversion is [TryFrom] returning non-retryable codes back,
+/// see also [CommandRetCode::into_recoverable].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RecoverableError(CommandRetCode);
+
+impl RecoverableError {
+    /// The wrapped code
+    pub fn code(&self) -> &CommandRetCode {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> CommandRetCode {
+        self.0
+    }
+}
+
+impl TryFrom<CommandRetCode> for RecoverableError {
+    type Error = CommandRetCode;
+
+    fn try_from(code: CommandRetCode) -> Result<Self, Self::Error> {
+        if code.is_retryable() {
+            Ok(Self(code))
+        } else {
+            Err(code)
+        }
+    }
+}
+
+impl From<RecoverableError> for CommandRetCode {
+    fn from(e: RecoverableError) -> Self {
+        e.0
+    }
+}
+
+impl Display for RecoverableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "recoverable LVM error: {}", self.0)
+    }
+}
+
+impl Error for RecoverableError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl CommandRetCode {
+    /// # Wrap the code into [RecoverableError] if it's retryable
+    /// `None` for the codes which [CommandRetCode::is_retryable] rejects
+    pub fn into_recoverable(self) -> Option<RecoverableError> {
+        RecoverableError::try_from(self).ok()
+    }
+}
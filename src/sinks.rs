@@ -0,0 +1,148 @@
+//! Ready-made [LvmLogSink] implementations for logging frameworks, each behind its own feature.
+//!
+//! # slog
+//! Wire LVM logs to an async slog drain:
+//! ```
+//! # #[cfg(feature = "slog")] {
+//! use std::sync::Arc;
+//!
+//! use lvm_sys2::{lvm::Lvm, sinks::SlogLogSink};
+//! use slog::{Drain, o};
+//!
+//! let decorator = slog_term::TermDecorator::new().build();
+//! let drain = slog_term::FullFormat::new(decorator).build().fuse();
+//! let drain = slog_async::Async::new(drain).build().fuse();
+//! let logger = slog::Logger::root(drain, o!("component" => "lvm"));
+//!
+//! Lvm::set_log_sink(Arc::new(SlogLogSink::new(logger)));
+//! # }
+//! ```
+
+#[cfg(any(feature = "tracing", feature = "slog", feature = "log"))]
+use crate::lvm::{LogLevel, LvmLogSink};
+
+/// # Forwards LVM logs to [tracing]
+/// Register with `Lvm::set_log_sink(Arc::new(TracingLogSink::new()))`.
+/// Events carry `file` and `line` fields pointing to LVM sources, levels are mapped as:
+/// - FATAL, ERROR - error
+/// - PRINT - info
+/// - VERBOSE - debug
+/// - VERY_VERBOSE, DEBUG - trace
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingLogSink;
+
+#[cfg(feature = "tracing")]
+impl TracingLogSink {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl LvmLogSink for TracingLogSink {
+    fn log(&self, level: LogLevel, file: &str, line: i32, message: &str) {
+        match level {
+            LogLevel::FATAL | LogLevel::ERROR => tracing::error!(file, line, "{message}"),
+            LogLevel::PRINT => tracing::info!(file, line, "{message}"),
+            LogLevel::VERBOSE => tracing::debug!(file, line, "{message}"),
+            LogLevel::VERY_VERBOSE | LogLevel::DEBUG | LogLevel::UNKNOWN => {
+                tracing::trace!(file, line, "{message}")
+            }
+        }
+    }
+}
+
+/// # Forwards LVM logs to a [slog::Logger]
+/// Records carry `lvm_file`, `lvm_line` and `lvm_level` key-value pairs, levels are mapped as:
+/// - FATAL - critical
+/// - ERROR - error
+/// - PRINT - info
+/// - VERBOSE - debug
+/// - VERY_VERBOSE, DEBUG - trace
+#[cfg(feature = "slog")]
+#[derive(Debug, Clone)]
+pub struct SlogLogSink {
+    logger: slog::Logger,
+}
+
+#[cfg(feature = "slog")]
+impl SlogLogSink {
+    pub fn new(logger: slog::Logger) -> Self {
+        Self { logger }
+    }
+}
+
+#[cfg(feature = "slog")]
+impl LvmLogSink for SlogLogSink {
+    fn log(&self, level: LogLevel, file: &str, line: i32, message: &str) {
+        let l = &self.logger;
+        match level {
+            LogLevel::FATAL => {
+                slog::crit!(l, "{}", message; "lvm_file" => file, "lvm_line" => line, "lvm_level" => ?level)
+            }
+            LogLevel::ERROR => {
+                slog::error!(l, "{}", message; "lvm_file" => file, "lvm_line" => line, "lvm_level" => ?level)
+            }
+            LogLevel::PRINT => {
+                slog::info!(l, "{}", message; "lvm_file" => file, "lvm_line" => line, "lvm_level" => ?level)
+            }
+            LogLevel::VERBOSE => {
+                slog::debug!(l, "{}", message; "lvm_file" => file, "lvm_line" => line, "lvm_level" => ?level)
+            }
+            LogLevel::VERY_VERBOSE | LogLevel::DEBUG | LogLevel::UNKNOWN => {
+                slog::trace!(l, "{}", message; "lvm_file" => file, "lvm_line" => line, "lvm_level" => ?level)
+            }
+        }
+    }
+}
+
+/// Levels are mapped the same way as for [LogCrateSink]
+#[cfg(feature = "log")]
+impl From<LogLevel> for log::Level {
+    fn from(value: LogLevel) -> Self {
+        match value {
+            LogLevel::FATAL | LogLevel::ERROR => Self::Error,
+            LogLevel::PRINT => Self::Info,
+            LogLevel::VERBOSE => Self::Debug,
+            LogLevel::VERY_VERBOSE | LogLevel::DEBUG | LogLevel::UNKNOWN => Self::Trace,
+        }
+    }
+}
+
+/// # Forwards LVM logs to the [log] facade
+/// Works with any `log` backend, e.g. `env_logger`. Records have `lvm` target,
+/// their file and line point to LVM sources. Levels are mapped as:
+/// - FATAL, ERROR - error
+/// - PRINT - info
+/// - VERBOSE - debug
+/// - VERY_VERBOSE, DEBUG - trace
+#[cfg(feature = "log")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogCrateSink;
+
+#[cfg(feature = "log")]
+impl LogCrateSink {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(feature = "log")]
+impl LvmLogSink for LogCrateSink {
+    fn log(&self, level: LogLevel, file: &str, line: i32, message: &str) {
+        let level = log::Level::from(level);
+        if !log::log_enabled!(target: "lvm", level) {
+            return;
+        }
+        log::logger().log(
+            &log::Record::builder()
+                .args(format_args!("{message}"))
+                .level(level)
+                .target("lvm")
+                .file(Some(file))
+                .line(u32::try_from(line).ok())
+                .build(),
+        );
+    }
+}
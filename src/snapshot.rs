@@ -0,0 +1,137 @@
+//! Snapshots management, see [SnapshotManager] and "Snapshot" section of `man 7 lvm`.
+
+use crate::{
+    lv::{LvCreateOptions, lv_path_arg, quote, validate_lv_name},
+    lvm::{CommandRetCode, Lvm},
+    report::{LvInfo, lvs_from, select_arg},
+    units::ByteSize,
+    vg::validate_vg_name,
+};
+
+/// # COW snapshots
+/// Obtained with [Lvm::snapshot]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SnapshotManager;
+
+impl Lvm {
+    /// Access snapshot commands
+    pub fn snapshot() -> SnapshotManager {
+        SnapshotManager
+    }
+
+    /// # How full the snapshot is
+    /// Runs `lvs -o +snap_percent --select 'lv_name=<snap_lv>' VG` and returns `snap_percent` (0.0 - 100.0).
+    /// A snapshot becomes invalid and loses its data once it's full, so monitoring should alert way before that.
+    ///
+    /// Returns [CommandRetCode::SnapshotInvalid] if the LV isn't a valid snapshot,
+    /// [CommandRetCode::UnexpectedOutput] if there's no such LV.
+    pub fn snapshot_usage(vg: &str, snap_lv: &str) -> Result<f64, CommandRetCode> {
+        let command = format!(
+            "lvs -o +snap_percent {} {}",
+            select_arg(&format!("lv_name={}", validate_lv_name(snap_lv)?))?,
+            quote(validate_vg_name(vg)?)
+        );
+        let lv = lvs_from(&command)?
+            .into_iter()
+            .next()
+            .ok_or(CommandRetCode::UnexpectedOutput(command))?;
+        match lv.snap_percent {
+            Some(percent) if lv.lv_attr.starts_with('s') => Ok(percent),
+            _ => Err(CommandRetCode::SnapshotInvalid),
+        }
+    }
+
+    /// # List LVs detached from what they depend on
+    /// Runs `lvs --select 'lv_parent=""'` (top-level LVs, sub-LVs of RAID, thin pools, etc. aren't reported)
+    /// and keeps LVs [OrphanReason::of] finds orphaned. They usually hold space for nothing,
+    /// so it's a helper for storage hygiene monitoring.
+    pub fn lvs_orphaned() -> Result<Vec<OrphanedLv>, CommandRetCode> {
+        Ok(
+            lvs_from(&format!("lvs {}", select_arg(r#"lv_parent="""#)?))?
+                .into_iter()
+                .filter_map(|lv| {
+                    OrphanReason::of(&lv).map(|orphan_reason| OrphanedLv { lv, orphan_reason })
+                })
+                .collect(),
+        )
+    }
+}
+
+/// # Why an LV is orphaned
+/// See [Lvm::lvs_orphaned]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrphanReason {
+    /// Thin snapshot whose origin was removed, LVM reports it as a regular thin LV with empty `origin`
+    MissingOrigin,
+    /// COW snapshot which is invalid (`S` type or `I` state in `lv_attr`), e.g. it overflowed, so it doesn't track the origin anymore
+    DetachedSnapshot,
+    /// Anything else, e.g. an LV on missing PVs
+    Other(String),
+}
+
+impl OrphanReason {
+    /// # Why the LV is orphaned, `None` if it isn't
+    /// Looks at `lv_attr` and `origin`:
+    /// - thin LV (`V` type) with the activation skip flag (`k`, 10th character) and no `origin` is [OrphanReason::MissingOrigin].
+    ///   Thin snapshots get the flag on creation (see `auto_set_activation_skip` in lvm.conf) and keep it when the origin is removed,
+    ///   so a thin LV created with `--setactivationskip y` looks the same.
+    /// - invalid COW snapshot is [OrphanReason::DetachedSnapshot], COW snapshots can't lose their origin:
+    ///   removing the origin removes them.
+    /// - LV with partial health (`p`, 9th character) is [OrphanReason::Other]
+    pub fn of(lv: &LvInfo) -> Option<Self> {
+        let attr: Vec<char> = lv.lv_attr.chars().collect();
+        let (volume_type, state, health, skip_activation) =
+            (attr.first(), attr.get(4), attr.get(8), attr.get(9));
+        match (volume_type, state) {
+            (Some('V'), _) if skip_activation == Some(&'k') && lv.origin.is_none() => {
+                Some(Self::MissingOrigin)
+            }
+            (Some('S'), _) | (Some('s'), Some('I')) => Some(Self::DetachedSnapshot),
+            _ if health == Some(&'p') => Some(Self::Other("some PVs are missing".to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// # LV found by [Lvm::lvs_orphaned]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrphanedLv {
+    pub lv: LvInfo,
+    pub orphan_reason: OrphanReason,
+}
+
+impl SnapshotManager {
+    /// # Create a snapshot of `origin_lv`
+    /// `size` is space reserved for changes, the snapshot becomes invalid once it's full
+    pub fn create(
+        &self,
+        vg: &str,
+        origin_lv: &str,
+        snap_name: &str,
+        size: ByteSize,
+    ) -> Result<(), CommandRetCode> {
+        let options = LvCreateOptions::new(vg)
+            .name(snap_name)
+            .size(size)
+            .snapshot(origin_lv);
+        Lvm::run_quiet(&options.command()?)
+    }
+
+    /// # Merge the snapshot back into its origin
+    /// `lvconvert --merge VG/SNAP`
+    ///
+    /// If the origin is in use, merge is postponed till the origin is activated next time.
+    /// So [Ok] means the merge is scheduled, not that the data is already merged.
+    pub fn merge(&self, vg: &str, snap_lv: &str) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!("lvconvert --merge {}", lv_path_arg(vg, snap_lv)?))
+    }
+
+    /// # List snapshots in `vg`
+    /// Runs `lvs VG` (see [Lvm::lvs_by_vg]) and keeps LVs with `s` (valid) or `S` (invalid) volume type in `lv_attr`
+    pub fn list_snapshots(&self, vg: &str) -> Result<Vec<LvInfo>, CommandRetCode> {
+        Ok(Lvm::lvs_by_vg(vg)?
+            .into_iter()
+            .filter(|lv| lv.lv_attr.starts_with(['s', 'S']))
+            .collect())
+    }
+}
@@ -0,0 +1,134 @@
+//! Tags of VGs and LVs, see [Lvm::vg_tags_add] and "TAGS" in `man 8 lvm`.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{
+    lv::{LvChangeOptions, lv_path_arg, quote, validate_tag},
+    lvm::{CommandRetCode, Lvm},
+    report::{LvInfo, Report, lvs_from, parse_report},
+    vg::validate_vg_name,
+};
+
+impl Lvm {
+    /// # Tag a VG
+    /// Runs `vgchange --addtag <tag> ... VG`, tags the VG already has are kept.
+    ///
+    /// Returns [CommandRetCode::InvalidParameters] if no tags are given or a tag is invalid:
+    /// it may only contain ASCII alphanumerics and `_`, `+`, `.`, `-` up to 128 characters.
+    pub fn vg_tags_add(vg: &str, tags: &[&str]) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&vg_tags_command(vg, true, tags)?)
+    }
+
+    /// # Untag a VG
+    /// Runs `vgchange --deltag <tag> ... VG`, see [Lvm::vg_tags_add] for the errors
+    pub fn vg_tags_remove(vg: &str, tags: &[&str]) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&vg_tags_command(vg, false, tags)?)
+    }
+
+    /// # Tag an LV
+    /// Runs `lvchange --addtag <tag> ... VG/LV`, see [Lvm::vg_tags_add] for the errors
+    pub fn lv_tags_add(vg: &str, lv: &str, tags: &[&str]) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&lv_tags_command(vg, lv, true, tags)?)
+    }
+
+    /// # Untag an LV
+    /// Runs `lvchange --deltag <tag> ... VG/LV`, see [Lvm::vg_tags_add] for the errors
+    pub fn lv_tags_remove(vg: &str, lv: &str, tags: &[&str]) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&lv_tags_command(vg, lv, false, tags)?)
+    }
+
+    /// # List LVs having the tag
+    /// Runs `lvs --select 'lv_tags={tag}'`, which matches LVs whose tags include the given one exactly
+    pub fn lvs_by_tag(tag: &str) -> Result<Vec<LvInfo>, CommandRetCode> {
+        lvs_from(&format!(
+            "lvs --select 'lv_tags={{{}}}'",
+            validate_tag(tag)?
+        ))
+    }
+
+    /// # List LVs having the tag
+    /// Alias of [Lvm::lvs_by_tag], which is the one to use in new code
+    pub fn lvs_with_tag(tag: &str) -> Result<Vec<LvInfo>, CommandRetCode> {
+        Self::lvs_by_tag(tag)
+    }
+
+    /// # Tags of an LV
+    /// Runs `lvs -o lv_tags VG/LV`, the list is empty if the LV has no tags
+    pub fn lv_tags(vg: &str, lv: &str) -> Result<Vec<String>, CommandRetCode> {
+        tags_field(
+            format!("lvs -o lv_tags {}", lv_path_arg(vg, lv)?),
+            "lv_tags",
+        )
+    }
+
+    /// # Tags of a VG
+    /// Runs `vgs -o vg_tags VG`, the list is empty if the VG has no tags
+    pub fn vg_tags(vg: &str) -> Result<Vec<String>, CommandRetCode> {
+        tags_field(
+            format!("vgs -o vg_tags {}", quote(validate_vg_name(vg)?)),
+            "vg_tags",
+        )
+    }
+}
+
+/// Split tags as LVM reports them: `lv_tags` / `vg_tags` are comma-separated, e.g. `backup,daily`, empty if there are no tags
+fn parse_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The tags field of a single VG / LV, LVM fails if there's no such object
+fn tags_field(command: String, field: &str) -> Result<Vec<String>, CommandRetCode> {
+    let reports: Report<HashMap<String, Vec<HashMap<String, String>>>> =
+        parse_report(Value::Object(Lvm::run(&command)?))?;
+    let tags = reports
+        .report
+        .into_iter()
+        .flat_map(|r| r.into_values().flatten())
+        .find_map(|mut row| row.remove(field))
+        .ok_or(CommandRetCode::UnexpectedOutput(command))?;
+    Ok(parse_tags(&tags))
+}
+
+/// # `vgchange --addtag` / `--deltag` command
+/// See [Lvm::vg_tags_add]
+pub(crate) fn vg_tags_command(
+    vg: &str,
+    add: bool,
+    tags: &[&str],
+) -> Result<String, CommandRetCode> {
+    if tags.is_empty() {
+        return Err(CommandRetCode::InvalidParameters);
+    }
+    let option = if add { "--addtag" } else { "--deltag" };
+    let mut args = vec!["vgchange".to_string()];
+    for tag in tags {
+        args.push(format!("{option} {}", quote(validate_tag(tag)?)));
+    }
+    args.push(quote(validate_vg_name(vg)?));
+    Ok(args.join(" "))
+}
+
+/// # `lvchange --addtag` / `--deltag` command
+/// See [Lvm::lv_tags_add]
+pub(crate) fn lv_tags_command(
+    vg: &str,
+    lv: &str,
+    add: bool,
+    tags: &[&str],
+) -> Result<String, CommandRetCode> {
+    let options = tags.iter().fold(LvChangeOptions::new(), |options, tag| {
+        if add {
+            options.add_tag(tag)
+        } else {
+            options.del_tag(tag)
+        }
+    });
+    // no tags means no changes, which is InvalidParameters already
+    options.command(vg, lv)
+}
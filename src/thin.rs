@@ -0,0 +1,94 @@
+//! Thin provisioning, see [ThinPoolManager] and `man 7 lvmthin`.
+
+use crate::{
+    lv::{LvCreateOptions, LvType},
+    lvm::{CommandRetCode, Lvm},
+    units::ByteSize,
+};
+
+/// Largest virtual size of a thin LV
+pub const MAX_THIN_LV_SIZE: ByteSize = ByteSize::from_tib(16);
+/// Smallest thin pool chunk size
+pub const MIN_CHUNK_SIZE: ByteSize = ByteSize::from_kib(64);
+/// Largest thin pool chunk size
+pub const MAX_CHUNK_SIZE: ByteSize = ByteSize::from_gib(1);
+
+/// # Thin pools and thin LVs
+/// Obtained with [Lvm::thin].
+/// Thin LVs are created in two steps: a pool with [ThinPoolManager::create_pool] and LVs in it with [ThinPoolManager::create_thin_lv].
+/// On top of [crate::lv::LvManager] validations, sizes are checked against LVM limits
+/// and [CommandRetCode::InvalidParameters] is returned if they are out of range.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThinPoolManager;
+
+impl Lvm {
+    /// Access thin provisioning commands
+    pub fn thin() -> ThinPoolManager {
+        ThinPoolManager
+    }
+}
+
+impl ThinPoolManager {
+    /// # Create a thin pool
+    /// `chunk_size` must be a power of two between [MIN_CHUNK_SIZE] and [MAX_CHUNK_SIZE], LVM picks one if it's not set
+    pub fn create_pool(
+        &self,
+        vg: &str,
+        name: &str,
+        size: ByteSize,
+        chunk_size: Option<ByteSize>,
+    ) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&pool_options(vg, name, size, chunk_size)?.command()?)
+    }
+
+    /// # Create a thin LV in the `pool`
+    /// `virtual_size` must not exceed [MAX_THIN_LV_SIZE]
+    pub fn create_thin_lv(
+        &self,
+        vg: &str,
+        pool: &str,
+        name: &str,
+        virtual_size: ByteSize,
+    ) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&thin_lv_options(vg, pool, name, virtual_size)?.command()?)
+    }
+}
+
+/// `lvcreate` options for a thin pool
+pub(crate) fn pool_options(
+    vg: &str,
+    name: &str,
+    size: ByteSize,
+    chunk_size: Option<ByteSize>,
+) -> Result<LvCreateOptions, CommandRetCode> {
+    let options = LvCreateOptions::new(vg)
+        .lv_type(LvType::ThinPool)
+        .name(name)
+        .size(size);
+    match chunk_size {
+        Some(chunk)
+            if !chunk.0.is_power_of_two() || chunk < MIN_CHUNK_SIZE || chunk > MAX_CHUNK_SIZE =>
+        {
+            Err(CommandRetCode::InvalidParameters)
+        }
+        Some(chunk) => Ok(options.chunk_size(chunk)),
+        None => Ok(options),
+    }
+}
+
+/// `lvcreate` options for a thin LV
+pub(crate) fn thin_lv_options(
+    vg: &str,
+    pool: &str,
+    name: &str,
+    virtual_size: ByteSize,
+) -> Result<LvCreateOptions, CommandRetCode> {
+    if virtual_size > MAX_THIN_LV_SIZE {
+        return Err(CommandRetCode::InvalidParameters);
+    }
+    Ok(LvCreateOptions::new(vg)
+        .lv_type(LvType::Thin)
+        .name(name)
+        .virtual_size(virtual_size)
+        .thin_pool(pool))
+}
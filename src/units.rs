@@ -0,0 +1,153 @@
+//! Units used in LVM reports and commands.
+//! LVM prints sizes in a human-readable form like `<10.00g`, [ByteSize] turns them back into bytes.
+//! All units are powers of 1024, as LVM's lowercase suffixes are.
+
+use std::{
+    error::Error,
+    fmt::Display,
+    ops::{Add, Sub},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, de};
+
+/// # Size in bytes
+/// Parsed from LVM size strings, e.g. `"<10.00g"`, `"512.00m"`, `"0 "` or `"4096B"` (`--units b`).
+/// The `<` / `>` prefix LVM uses to mark rounded values is dropped, so the value is approximate in that case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(pub u64);
+
+/// LVM unit suffixes, from the largest
+const UNITS: &[(char, u64)] = &[
+    ('e', 1 << 60),
+    ('p', 1 << 50),
+    ('t', 1 << 40),
+    ('g', 1 << 30),
+    ('m', 1 << 20),
+    ('k', 1 << 10),
+];
+
+/// # Constructors from binary units
+/// Sizes above `u64::MAX` bytes (16 EiB) don't fit, so they panic instead of wrapping,
+/// which is a compile error in `const` context, e.g. `const SIZE: ByteSize = ByteSize::from_pib(1 << 14)`.
+impl ByteSize {
+    /// # Panics
+    /// If `kib` is over `u64::MAX >> 10`
+    pub const fn from_kib(kib: u64) -> Self {
+        scaled(kib, 1 << 10)
+    }
+
+    /// # Panics
+    /// If `mib` is over `u64::MAX >> 20`
+    pub const fn from_mib(mib: u64) -> Self {
+        scaled(mib, 1 << 20)
+    }
+
+    /// # Panics
+    /// If `gib` is over `u64::MAX >> 30`
+    pub const fn from_gib(gib: u64) -> Self {
+        scaled(gib, 1 << 30)
+    }
+
+    /// # Panics
+    /// If `tib` is over `u64::MAX >> 40`
+    pub const fn from_tib(tib: u64) -> Self {
+        scaled(tib, 1 << 40)
+    }
+
+    /// # Panics
+    /// If `pib` is over `u64::MAX >> 50`
+    pub const fn from_pib(pib: u64) -> Self {
+        scaled(pib, 1 << 50)
+    }
+}
+
+/// `value * multiplier` bytes, see [ByteSize::from_kib]
+const fn scaled(value: u64, multiplier: u64) -> ByteSize {
+    match value.checked_mul(multiplier) {
+        Some(bytes) => ByteSize(bytes),
+        None => panic!("size overflows u64 bytes"),
+    }
+}
+
+/// Size string couldn't be parsed, contains the original string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseByteSizeError(pub String);
+
+impl Display for ParseByteSizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid LVM size: {:?}", self.0)
+    }
+}
+
+impl Error for ParseByteSizeError {}
+
+impl FromStr for ByteSize {
+    type Err = ParseByteSizeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseByteSizeError(s.to_string());
+        let value = s.trim().trim_start_matches(['<', '>']);
+        // LVM uses lowercase suffixes for powers of 1024
+        let (number, multiplier) = match value.char_indices().last() {
+            Some((idx, unit)) if unit.is_ascii_alphabetic() => {
+                let multiplier = match unit {
+                    'b' | 'B' => 1,
+                    's' | 'S' => 512,
+                    _ => match UNITS.iter().find(|(u, _)| *u == unit) {
+                        Some((_, multiplier)) => *multiplier,
+                        None => return Err(err()),
+                    },
+                };
+                (&value[..idx], multiplier)
+            }
+            _ => (value, 1),
+        };
+        let number = number.trim();
+        if let Ok(bytes) = number.parse::<u64>() {
+            return bytes.checked_mul(multiplier).map(Self).ok_or_else(err);
+        }
+        match number.parse::<f64>() {
+            Ok(n) if n.is_finite() && n >= 0.0 => Ok(Self((n * multiplier as f64).round() as u64)),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl Display for ByteSize {
+    /// The way LVM prints sizes - in the largest unit with 2 decimals, e.g. `10.00g` or `512b` for small sizes.
+    /// It's rounded, so parsing it back may give a slightly different value.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match UNITS.iter().find(|(_, multiplier)| self.0 >= *multiplier) {
+            Some((unit, multiplier)) => {
+                write!(f, "{:.2}{unit}", self.0 as f64 / *multiplier as f64)
+            }
+            None => write!(f, "{}b", self.0),
+        }
+    }
+}
+
+impl Add for ByteSize {
+    type Output = Self;
+
+    /// Saturates at `u64::MAX` bytes
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl Sub for ByteSize {
+    type Output = Self;
+
+    /// Saturates at 0, e.g. free space can't go negative
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,106 @@
+//! VDO (deduplicated and compressed) LVs, see [VdoLvOptions] and `man 7 lvmvdo`.
+
+use crate::{
+    lv::LvCreateOptions,
+    lvm::{CommandRetCode, Lvm},
+    units::ByteSize,
+    version::LvmVersion,
+};
+
+/// Largest virtual size of a VDO LV
+pub const MAX_VDO_VIRTUAL_SIZE: ByteSize = ByteSize::from_pib(4);
+/// First LVM version with native VDO support
+pub const MIN_VDO_VERSION: LvmVersion = LvmVersion::new(2, 3, 3);
+
+/// # VDO pool of [LvCreateOptions::vdo]
+/// `lvcreate --type vdo` creates the pool holding the data and the VDO LV on top of it at once.
+/// Compression and deduplication are on by default, as in LVM.
+///
+/// ```
+/// use lvm_sys2::{lv::LvCreateOptions, units::ByteSize, vdo::VdoLvOptions};
+/// let vdo = VdoLvOptions::new("vpool", ByteSize::from_gib(10), ByteSize::from_gib(100)).compression(false);
+/// let cmd = LvCreateOptions::new("vg0").name("vdo0").vdo(vdo).command();
+/// assert_eq!(
+///     cmd.unwrap(),
+///     "lvcreate --type vdo -L 10737418240b -V 107374182400b --compression n --deduplication y -n 'vdo0' 'vg0/vpool'"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct VdoLvOptions {
+    pub(crate) pool_name: String,
+    pub(crate) pool_size: ByteSize,
+    pub(crate) virtual_size: ByteSize,
+    compression: bool,
+    deduplication: bool,
+}
+
+impl VdoLvOptions {
+    /// `pool_size` of physical space for `virtual_size` of the VDO LV
+    pub fn new(pool_name: &str, pool_size: ByteSize, virtual_size: ByteSize) -> Self {
+        Self {
+            pool_name: pool_name.to_string(),
+            pool_size,
+            virtual_size,
+            compression: true,
+            deduplication: true,
+        }
+    }
+
+    /// `--compression y|n`
+    pub fn compression(mut self, compression: bool) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// `--deduplication y|n`
+    pub fn deduplication(mut self, deduplication: bool) -> Self {
+        self.deduplication = deduplication;
+        self
+    }
+
+    /// Pool name is validated along with the VG one as a part of the `VG/pool` argument
+    pub(crate) fn args(&self) -> Result<Vec<String>, CommandRetCode> {
+        if self.virtual_size > MAX_VDO_VIRTUAL_SIZE {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        let yn = |on| if on { "y" } else { "n" };
+        Ok(vec![
+            format!("--compression {}", yn(self.compression)),
+            format!("--deduplication {}", yn(self.deduplication)),
+        ])
+    }
+}
+
+impl Lvm {
+    /// # Create a VDO pool
+    /// LVM always creates the VDO LV along with the pool, it gets a generated name (e.g. `lvol0`) here,
+    /// see [Lvm::create_vdo_lv] to name it.
+    /// Returns [CommandRetCode::FeatureNotSupported] if LVM is older than [MIN_VDO_VERSION].
+    pub fn create_vdo_pool(vg: &str, options: &VdoLvOptions) -> Result<(), CommandRetCode> {
+        ensure_vdo_supported()?;
+        Lvm::run_quiet(&LvCreateOptions::new(vg).vdo(options.clone()).command()?)
+    }
+
+    /// # Create a VDO LV `name` along with its pool
+    /// See [Lvm::create_vdo_pool]
+    pub fn create_vdo_lv(
+        vg: &str,
+        name: &str,
+        options: &VdoLvOptions,
+    ) -> Result<(), CommandRetCode> {
+        ensure_vdo_supported()?;
+        Lvm::run_quiet(
+            &LvCreateOptions::new(vg)
+                .name(name)
+                .vdo(options.clone())
+                .command()?,
+        )
+    }
+}
+
+fn ensure_vdo_supported() -> Result<(), CommandRetCode> {
+    if Lvm::version()? < MIN_VDO_VERSION {
+        return Err(CommandRetCode::FeatureNotSupported);
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//! LVM version detection, see [Lvm::version].
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::lvm::{CommandRetCode, Lvm};
+
+/// # LVM tools version
+/// Ordered, so it's handy to gate features, e.g. `version >= LvmVersion::new(2, 2, 99)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LvmVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl LvmVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl Display for LvmVersion {
+    /// The same way LVM prints it, e.g. `2.03.30`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{:02}.{:02}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for LvmVersion {
+    type Err = CommandRetCode;
+
+    /// Parses `2.03.30`, build suffixes like in `2.03.30(2)` are ignored
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || CommandRetCode::UnexpectedOutput(s.to_string());
+        let version = s.trim().split(['(', ' ', '-']).next().ok_or_else(err)?;
+        let mut parts = version
+            .split('.')
+            .map(|p| p.parse::<u32>().map_err(|_e| err()));
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(major), Some(minor), Some(patch), None) => Ok(Self::new(major?, minor?, patch?)),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl Lvm {
+    /// # Get LVM version
+    /// Runs `version` and parses the `LVM version:` line of its output.
+    /// Returns [CommandRetCode::UnexpectedOutput] with the whole output if there's no such line.
+    pub fn version() -> Result<LvmVersion, CommandRetCode> {
+        // version doesn't support --reportformat
+        let output = Self::run_without_flags("version")?;
+        // messages are concatenated, the version is followed by a space and build details
+        let (_, rest) = output
+            .split_once("LVM version:")
+            .ok_or_else(|| CommandRetCode::UnexpectedOutput(output.clone()))?;
+        rest.split_whitespace().next().unwrap_or_default().parse()
+    }
+}
@@ -0,0 +1,296 @@
+//! Volume groups management, see [VgManager].
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::{
+    devices::devices_option,
+    error::NameValidationError,
+    lv::quote,
+    lvm::{CommandRetCode, Lvm, log_warning},
+    pv::device_arg,
+    report::{LvReport, Report, lvs_from, parse_report},
+};
+
+/// Longest VG / LV name LVM accepts
+pub(crate) const MAX_NAME_LEN: usize = 128;
+
+/// # Typed wrappers for VG commands
+/// Obtained with [Lvm::vg].
+/// Names are validated against LVM naming rules before running any command:
+/// only ASCII alphanumerics and `-`, `_`, `.`, `+` up to 128 characters.
+/// [CommandRetCode::InvalidParameters] is returned for invalid names and missing devices.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VgManager;
+
+impl Lvm {
+    /// Access VG commands
+    pub fn vg() -> VgManager {
+        VgManager
+    }
+
+    /// # Make VG invisible to the system, e.g. to move its PVs to another host
+    /// Runs `vgexport`, returns [CommandRetCode::VgHasActiveLvs] if any LV of the VG is active
+    pub fn vg_export(vg: &str) -> Result<(), CommandRetCode> {
+        let vg = validate_vg_name(vg)?;
+        if has_active_lvs(vg)? {
+            return Err(CommandRetCode::VgHasActiveLvs);
+        }
+        Lvm::run_quiet(&format!("vgexport {}", quote(vg)))
+    }
+
+    /// # Make exported VG known to the system again
+    /// Runs `vgimport --devices <devices> VG`, so LVM only scans the VG's `devices` (`--devices` needs LVM 2.03.12+).
+    /// All of them have to be present: [CommandRetCode::InvalidParameters] is returned for missing ones.
+    /// Returns [CommandRetCode::VgHasActiveLvs] if any LV of the VG is active.
+    pub fn vg_import(vg: &str, devices: &[&Path]) -> Result<(), CommandRetCode> {
+        let vg = validate_vg_name(vg)?;
+        devices_arg(devices)?;
+        let devices = devices_option(devices)?;
+        if importing_vg_has_active_lvs(vg, &devices)? {
+            return Err(CommandRetCode::VgHasActiveLvs);
+        }
+        Lvm::run_quiet(&format!("vgimport {devices} {}", quote(vg)))
+    }
+
+    /// # Scan devices for VGs
+    /// Runs `vgscan [--mknodes]`, `mknodes` re-creates missing `/dev` nodes of active LVs.
+    /// The command prints no report, so only success is reported.
+    pub fn vgscan(mknodes: bool) -> Result<(), CommandRetCode> {
+        let command = if mknodes {
+            "vgscan --mknodes"
+        } else {
+            "vgscan"
+        };
+        Self::run_quiet(command)
+    }
+
+    /// # Back up VG metadata into a file
+    /// Runs `vgcfgbackup -f <dest> <vg>`, the file is overwritten if it exists.
+    /// Returns [CommandRetCode::InvalidParameters] if the directory of `dest` doesn't exist.
+    pub fn metadata_backup(vg: &str, dest: &Path) -> Result<(), CommandRetCode> {
+        let dir = match dest.parent() {
+            Some(dir) if dir.as_os_str().is_empty() => Path::new("."),
+            Some(dir) => dir,
+            None => return Err(CommandRetCode::InvalidParameters),
+        };
+        if !dir.is_dir() {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        Lvm::run_quiet(&format!(
+            "vgcfgbackup -f {} {}",
+            path_arg(dest)?,
+            quote(validate_vg_name(vg)?)
+        ))
+    }
+
+    /// # Restore VG metadata from a file
+    /// Runs `vgcfgrestore -f <file> --force <vg>`.
+    /// It overwrites metadata on the PVs, which may lose data if the backup doesn't match the actual layout,
+    /// so `force` has to be `true` to confirm it, [CommandRetCode::InvalidParameters] is returned otherwise.
+    /// A warning goes to the log sink every time. `--force` also lets LVM restore VGs with thin pools.
+    pub fn metadata_restore(vg: &str, file: &Path, force: bool) -> Result<(), CommandRetCode> {
+        let vg = validate_vg_name(vg)?;
+        if !force || !file.is_file() {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        let file = path_arg(file)?;
+        log_warning(
+            file!(),
+            line!(),
+            &format!(
+                "restoring metadata of VG {vg} from {file}, it may lose data if the backup is outdated"
+            ),
+        );
+        Lvm::run_quiet(&format!("vgcfgrestore -f {file} --force {}", quote(vg)))
+    }
+
+    /// # Move PVs of a VG to a new VG
+    /// Runs `vgsplit SRC DST <pvs...>`, LVs have to be entirely on the moved PVs and inactive.
+    ///
+    /// LVM would move the PVs to `dst_vg` if it exists, so it's checked with [Lvm::vgs] first:
+    /// [CommandRetCode::InvalidParameters] is returned for an existing `dst_vg`, use [Lvm::vg_merge] to join VGs.
+    pub fn vg_split(src_vg: &str, dst_vg: &str, pvs: &[&Path]) -> Result<(), CommandRetCode> {
+        let src_vg = validate_vg_name(src_vg)?;
+        let dst_vg = validate_vg_name(dst_vg)?;
+        let pvs = devices_arg(pvs)?;
+        if Lvm::vgs()?.iter().any(|vg| vg.vg_name == dst_vg) {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        Lvm::run_quiet(&format!(
+            "vgsplit {} {} {pvs}",
+            quote(src_vg),
+            quote(dst_vg)
+        ))
+    }
+
+    /// # Merge a VG into another one
+    /// Runs `vgmerge DST SRC`, `src_vg` disappears. Its LVs have to be inactive
+    /// and the VGs need compatible extent sizes.
+    pub fn vg_merge(dst_vg: &str, src_vg: &str) -> Result<(), CommandRetCode> {
+        let dst_vg = validate_vg_name(dst_vg)?;
+        let src_vg = validate_vg_name(src_vg)?;
+        if dst_vg == src_vg {
+            return Err(CommandRetCode::InvalidParameters);
+        }
+        Lvm::run_quiet(&format!("vgmerge {} {}", quote(dst_vg), quote(src_vg)))
+    }
+
+    /// # Check VG metadata consistency
+    /// Runs `vgck VG`, which fails with [CommandRetCode::ProcessingFailed] when metadata is inconsistent,
+    /// e.g. copies on PVs differ. That's an expected outcome of the check, so it's reported as an inconsistent [VgConsistencyReport]
+    /// with the errors LVM logged. Other failures, e.g. a missing VG, are returned as errors.
+    pub fn check_vg_consistency(vg: &str) -> Result<VgConsistencyReport, CommandRetCode> {
+        match Lvm::run_quiet_with_errors(&format!("vgck {}", quote(validate_vg_name(vg)?))) {
+            Ok(()) => Ok(VgConsistencyReport {
+                is_consistent: true,
+                issues: vec![],
+            }),
+            Err((CommandRetCode::ProcessingFailed, issues)) => Ok(VgConsistencyReport {
+                is_consistent: false,
+                issues,
+            }),
+            Err((code, _messages)) => Err(code),
+        }
+    }
+}
+
+/// # Result of [Lvm::check_vg_consistency]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VgConsistencyReport {
+    pub is_consistent: bool,
+    /// FATAL / ERROR messages of `vgck`, empty for a consistent VG
+    pub issues: Vec<String>,
+}
+
+/// Quoted path, which doesn't need to exist
+fn path_arg(path: &Path) -> Result<String, CommandRetCode> {
+    match path.to_str() {
+        Some(s) if !s.is_empty() && !s.contains(|c: char| c.is_whitespace() || c == '\'') => {
+            Ok(quote(s))
+        }
+        _ => Err(CommandRetCode::InvalidParameters),
+    }
+}
+
+/// Whether any LV of the VG is active
+fn has_active_lvs(vg: &str) -> Result<bool, CommandRetCode> {
+    Ok(lvs_from(&format!("lvs {}", quote(vg)))?
+        .iter()
+        .any(|lv| lv.is_active()))
+}
+
+/// Same as [has_active_lvs], but LVM refuses to report exported VGs, which means there are no active LVs
+fn importing_vg_has_active_lvs(vg: &str, devices: &str) -> Result<bool, CommandRetCode> {
+    match Lvm::run_with_errors(&format!("lvs {devices} {}", quote(vg))) {
+        Ok(report) => {
+            let reports: Report<LvReport> = parse_report(Value::Object(report))?;
+            Ok(reports
+                .report
+                .iter()
+                .flat_map(|r| &r.lv)
+                .any(|lv| lv.is_active()))
+        }
+        Err((CommandRetCode::ProcessingFailed, messages))
+            if messages.iter().any(|m| {
+                m.contains(" is exported")
+                    || (m.starts_with("Volume group ") && m.contains(" not found"))
+            }) =>
+        {
+            Ok(false)
+        }
+        Err((code, _messages)) => Err(code),
+    }
+}
+
+impl VgManager {
+    /// `vgcreate <name> <devices...>`
+    pub fn vgcreate(&self, name: &str, devices: &[&Path]) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!(
+            "vgcreate {} {}",
+            quote(validate_vg_name(name)?),
+            devices_arg(devices)?
+        ))
+    }
+
+    /// `vgremove <name>`
+    pub fn vgremove(&self, name: &str) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!("vgremove {}", quote(validate_vg_name(name)?)))
+    }
+
+    /// `vgextend <name> <devices...>`
+    pub fn vgextend(&self, name: &str, devices: &[&Path]) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!(
+            "vgextend {} {}",
+            quote(validate_vg_name(name)?),
+            devices_arg(devices)?
+        ))
+    }
+
+    /// `vgreduce <name> <devices...>`
+    pub fn vgreduce(&self, name: &str, devices: &[&Path]) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!(
+            "vgreduce {} {}",
+            quote(validate_vg_name(name)?),
+            devices_arg(devices)?
+        ))
+    }
+
+    /// `vgrename <old> <new>`
+    pub fn vgrename(&self, old: &str, new: &str) -> Result<(), CommandRetCode> {
+        Lvm::run_quiet(&format!(
+            "vgrename {} {}",
+            quote(validate_vg_name(old)?),
+            quote(validate_vg_name(new)?)
+        ))
+    }
+}
+
+/// # Check VG name against LVM rules
+/// ASCII alphanumerics and `_`, `+`, `.`, `-` up to 128 characters, but not `.`, `..` or starting with `-`,
+/// see "VALID NAMES" in `man 8 lvm`. Lets validating user input before running any command.
+pub fn vg_validate_name(name: &str) -> Result<(), NameValidationError> {
+    if name.is_empty() {
+        return Err(NameValidationError::Empty);
+    }
+    if name.len() > MAX_NAME_LEN {
+        return Err(NameValidationError::TooLong {
+            max: MAX_NAME_LEN,
+            actual: name.len(),
+        });
+    }
+    let invalid: String = name
+        .chars()
+        .filter(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+')))
+        .collect();
+    if !invalid.is_empty() {
+        return Err(NameValidationError::InvalidChars(invalid));
+    }
+    if name.starts_with('-') {
+        return Err(NameValidationError::InvalidChars("-".to_string()));
+    }
+    match name {
+        "." => Err(NameValidationError::ReservedWord(".")),
+        ".." => Err(NameValidationError::ReservedWord("..")),
+        _ => Ok(()),
+    }
+}
+
+/// VG name if it's valid, see [vg_validate_name]
+pub(crate) fn validate_vg_name(name: &str) -> Result<&str, CommandRetCode> {
+    vg_validate_name(name)?;
+    Ok(name)
+}
+
+/// Space-separated list of existing devices, at least one is required
+fn devices_arg(devices: &[&Path]) -> Result<String, CommandRetCode> {
+    if devices.is_empty() {
+        return Err(CommandRetCode::InvalidParameters);
+    }
+    Ok(devices
+        .iter()
+        .map(|d| device_arg(d))
+        .collect::<Result<Vec<_>, _>>()?
+        .join(" "))
+}
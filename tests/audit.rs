@@ -0,0 +1,32 @@
+// own test binary: the audit log is process-wide
+use std::{fs, path::Path};
+
+use lvm_sys2::lvm::Lvm;
+use serde_json::Value;
+
+#[test]
+fn audit_log_in_missing_dir() {
+    assert!(Lvm::enable_audit_log(Path::new("/nonexistent/audit.log")).is_err());
+}
+
+#[test]
+fn audit_log_records_commands() {
+    let path = std::env::temp_dir().join(format!("lvm-sys2-audit-{}.log", std::process::id()));
+    Lvm::enable_audit_log(&path).unwrap();
+    Lvm::run("vgs").unwrap();
+    Lvm::disable_audit_log();
+    Lvm::run("pvs").unwrap();
+
+    let log = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    let records: Vec<Value> = log
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["cmd"], "vgs --reportformat json");
+    assert_eq!(records[0]["retcode"], "CommandSucceeded");
+    assert!(records[0]["duration_ms"].is_u64());
+    let ts = records[0]["ts"].as_str().unwrap();
+    assert!(ts.ends_with('Z') && ts.len() == "2024-01-31T12:00:00.000Z".len());
+}
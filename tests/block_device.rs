@@ -0,0 +1,26 @@
+#![cfg(feature = "nix")]
+
+use std::path::Path;
+
+use lvm_sys2::lvm::{CommandRetCode, Lvm};
+use rstest::rstest;
+
+#[rstest]
+#[case("/dev/null")]
+#[case("/dev/lvm-sys2-missing-device")]
+#[case("/etc/hostname")]
+#[case("/dev")]
+fn not_a_block_device(#[case] path: &str) {
+    assert_eq!(
+        Lvm::validate_block_device(Path::new(path)).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[test]
+fn pvcreate_rejects_non_block_devices() {
+    assert_eq!(
+        Lvm::pv().create(Path::new("/dev/null")).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
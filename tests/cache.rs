@@ -0,0 +1,21 @@
+use lvm_sys2::{
+    cache::CachePolicy,
+    lvm::{CommandRetCode, Lvm},
+    units::ByteSize,
+};
+
+#[test]
+fn invalid_cache_arguments() {
+    assert_eq!(
+        Lvm::attach_cache("vg0", "data", "vg1/cpool"),
+        Err(CommandRetCode::InvalidParameters)
+    );
+    assert_eq!(
+        Lvm::detach_cache("vg 0", "data"),
+        Err(CommandRetCode::InvalidParameters)
+    );
+    assert_eq!(
+        Lvm::create_cache_pool("vg0", "cpool", ByteSize(0), None, CachePolicy::Smq),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
@@ -0,0 +1,27 @@
+use lvm_sys2::{
+    command_builder::{CommandBuildError, LvmCommandBuilder},
+    units::ByteSize,
+};
+use rstest::rstest;
+
+#[test]
+fn lvcreate_without_name() {
+    let cmd = LvmCommandBuilder::new()
+        .lvcreate()
+        .size(ByteSize(4096))
+        .vg("vg0")
+        .build();
+    assert_eq!(cmd, Ok("lvcreate -L 4096b vg0".to_string()));
+}
+
+#[rstest]
+#[case(LvmCommandBuilder::new().size(ByteSize(1)).vg("vg"), CommandBuildError::MissingCommand)]
+#[case(LvmCommandBuilder::new().lvcreate().vg("vg"), CommandBuildError::MissingArgument("size"))]
+#[case(LvmCommandBuilder::new().lvcreate().size(ByteSize(1)), CommandBuildError::MissingArgument("vg"))]
+#[case(LvmCommandBuilder::new().lvcreate().size(ByteSize(0)).vg("vg"), CommandBuildError::ZeroSize)]
+#[case(LvmCommandBuilder::new().lvcreate().size(ByteSize(1)).vg("vg").name("a b"), CommandBuildError::InvalidName("a b".to_string()))]
+#[case(LvmCommandBuilder::new().lvcreate().size(ByteSize(1)).vg("vg;rm"), CommandBuildError::InvalidName("vg;rm".to_string()))]
+#[case(LvmCommandBuilder::new().lvcreate().size(ByteSize(1)).vg("vg").name("#lv"), CommandBuildError::InvalidName("#lv".to_string()))]
+fn build_errors(#[case] builder: LvmCommandBuilder, #[case] expected: CommandBuildError) {
+    assert_eq!(builder.build(), Err(expected));
+}
@@ -0,0 +1,130 @@
+use std::ffi::CString;
+
+use lvm_sys2::lvm::CommandRetCode;
+use rstest::rstest;
+
+fn json_failure(data: &str) -> CommandRetCode {
+    let e = serde_json::from_str::<serde_json::Value>(data).unwrap_err();
+    CommandRetCode::JsonDeserializationFailed((e, data.to_string()))
+}
+
+fn nul_failure() -> CommandRetCode {
+    CommandRetCode::InvalidCommandLine(CString::new("pvs\0").unwrap_err())
+}
+
+#[rstest]
+#[case(CommandRetCode::ProcessingFailed)]
+#[case(CommandRetCode::Unknown(42))]
+#[case(nul_failure())]
+#[case(json_failure("{\"report\": "))]
+#[case(json_failure("not a json"))]
+#[case(CommandRetCode::RetriesExhausted(Box::new(CommandRetCode::ProcessingFailed)))]
+fn clone_round_trip(#[case] code: CommandRetCode) {
+    assert_eq!(code.clone(), code);
+}
+
+#[rstest]
+#[case(CommandRetCode::Unknown(42), CommandRetCode::Unknown(43))]
+#[case(CommandRetCode::ProcessingFailed, CommandRetCode::InitFailed)]
+#[case(json_failure("not a json"), json_failure("{"))]
+#[case(
+    CommandRetCode::UnexpectedOutput("a".to_string()),
+    CommandRetCode::UnexpectedOutput("b".to_string())
+)]
+#[case(
+    CommandRetCode::ClosurePanicked("a".to_string()),
+    CommandRetCode::ClosurePanicked("b".to_string())
+)]
+fn not_equal(#[case] a: CommandRetCode, #[case] b: CommandRetCode) {
+    assert_ne!(a, b);
+}
+
+#[rstest]
+#[case(CommandRetCode::ProcessingFailed, true)]
+#[case(CommandRetCode::Unknown(42), true)]
+#[case(CommandRetCode::GlobalStatePoisoned, false)]
+#[case(CommandRetCode::InitFailed, false)]
+#[case(nul_failure(), false)]
+#[case(CommandRetCode::InvalidParameters, false)]
+#[case(CommandRetCode::VgHasActiveLvs, false)]
+#[case(CommandRetCode::FeatureNotSupported, false)]
+#[case(CommandRetCode::LockManagerNotAvailable, false)]
+#[case(CommandRetCode::ClosurePanicked("boom".to_string()), false)]
+fn is_retryable(#[case] code: CommandRetCode, #[case] retryable: bool) {
+    assert_eq!(code.is_retryable(), retryable);
+}
+
+#[rstest]
+#[case(CommandRetCode::CommandSucceeded)]
+#[case(CommandRetCode::NoSuchCommand)]
+#[case(CommandRetCode::InvalidParameters)]
+#[case(CommandRetCode::InitFailed)]
+#[case(CommandRetCode::ProcessingFailed)]
+#[case(CommandRetCode::Unknown(42))]
+fn native_code_round_trip(#[case] code: CommandRetCode) {
+    assert_eq!(CommandRetCode::from(i32::from(code.clone())), code);
+}
+
+#[rstest]
+#[case(nul_failure(), 10)]
+#[case(CommandRetCode::GlobalStatePoisoned, 11)]
+#[case(CommandRetCode::Timeout, 14)]
+#[case(CommandRetCode::FeatureNotSupported, 18)]
+#[case(CommandRetCode::ClosurePanicked("boom".to_string()), 20)]
+#[case(CommandRetCode::AlreadyInitialized, 21)]
+#[case(CommandRetCode::PartialMode, 22)]
+#[case(CommandRetCode::SnapshotInvalid, 23)]
+#[case(CommandRetCode::InsufficientFreeSpace, 24)]
+#[case(CommandRetCode::JsonPointerNotFound("/report".to_string()), 25)]
+#[case(CommandRetCode::DataLossRisk, 26)]
+fn wrapper_codes(#[case] code: CommandRetCode, #[case] expected: i32) {
+    assert_eq!(i32::from(code), expected);
+}
+
+#[test]
+fn exit_code() {
+    use std::process::ExitCode;
+    assert_eq!(
+        ExitCode::from(CommandRetCode::CommandSucceeded),
+        ExitCode::SUCCESS
+    );
+    assert_eq!(
+        ExitCode::from(CommandRetCode::ProcessingFailed),
+        ExitCode::from(5)
+    );
+    assert_eq!(
+        ExitCode::from(CommandRetCode::Unknown(1000)),
+        ExitCode::from(255)
+    );
+}
+
+fn hash_of(code: &CommandRetCode) -> u64 {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+    let mut hasher = DefaultHasher::new();
+    code.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[rstest]
+#[case(CommandRetCode::Unknown(42))]
+#[case(nul_failure())]
+#[case(json_failure("not a json"))]
+#[case(CommandRetCode::RetriesExhausted(Box::new(CommandRetCode::Timeout)))]
+fn equal_codes_hash_equally(#[case] code: CommandRetCode) {
+    assert_eq!(hash_of(&code), hash_of(&code.clone()));
+}
+
+#[test]
+fn hash_set_deduplicates() {
+    use std::collections::HashSet;
+    let codes: HashSet<_> = [
+        CommandRetCode::Unknown(42),
+        CommandRetCode::Unknown(42),
+        CommandRetCode::Unknown(43),
+        CommandRetCode::ProcessingFailed,
+        CommandRetCode::ProcessingFailed,
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(codes.len(), 3);
+}
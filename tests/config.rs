@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use lvm_sys2::{
+    config::{ConfigValue, LvmConfig},
+    lvm::{CommandRetCode, Lvm},
+};
+use rstest::rstest;
+
+const CONFIG: &str = "config {\n\tcheckoptions=1\n}\ndevices {\n\tdir=\"/dev\"\n\tfilter=[\"a|^/dev/sd.*|\",\"r|.*|\"]\n}\nglobal {\n\tumask=63\n\tuse_lvmetad=0\n\tsuffix=1.5\n}\nbackup {\n\tbackup=1\n\tbackup_dir=\"/etc/lvm/backup\"\n}\n";
+
+#[test]
+fn parse_config() {
+    let config: LvmConfig = CONFIG.parse().unwrap();
+    assert_eq!(config.use_lvmetad, Some(false));
+    assert_eq!(config.backup, Some(true));
+    assert_eq!(config.devices_filter, vec!["a|^/dev/sd.*|", "r|.*|"]);
+    assert_eq!(
+        config.get("devices/dir"),
+        Some(&ConfigValue::String("/dev".to_string()))
+    );
+    assert_eq!(config.get("global/umask"), Some(&ConfigValue::Int(63)));
+    assert_eq!(config.get("global/suffix"), Some(&ConfigValue::Float(1.5)));
+}
+
+#[test]
+fn parse_concatenated_config() {
+    // captured messages lose line breaks
+    let config: LvmConfig = CONFIG.replace('\n', "").parse().unwrap();
+    assert_eq!(config, CONFIG.parse().unwrap());
+}
+
+#[test]
+fn parse_config_without_lvmetad() {
+    let config: LvmConfig = "devices {\tfilter=\"a|.*|\"\t}".parse().unwrap();
+    assert_eq!(config.use_lvmetad, None);
+    assert_eq!(config.backup, None);
+    assert_eq!(config.devices_filter, vec!["a|.*|"]);
+}
+
+#[rstest]
+#[case("global {")]
+#[case("global { umask }")]
+#[case("}")]
+#[case("global { dir=\"/dev }")]
+#[case("global { umask=abc }")]
+fn invalid_config(#[case] output: &str) {
+    assert!(matches!(
+        output.parse::<LvmConfig>(),
+        Err(CommandRetCode::UnexpectedOutput(_))
+    ));
+}
+
+#[test]
+fn run_with_config_rejects_unusable_files() {
+    assert_eq!(
+        Lvm::run_with_config("vgs", Path::new("/no/such/lvm.conf")).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    let path = std::env::temp_dir().join("lvm-sys2-quoted.conf");
+    std::fs::write(&path, "devices { filter = [ 'a|.*|' ] }").unwrap();
+    assert_eq!(
+        Lvm::run_with_config("vgs", &path).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[rstest]
+#[case("")]
+#[case("thin-perf")]
+#[case("../profile")]
+#[case("a b")]
+fn run_with_profile_rejects_invalid_names(#[case] profile: &str) {
+    assert_eq!(
+        Lvm::run_with_profile("vgs", profile).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
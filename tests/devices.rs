@@ -0,0 +1,32 @@
+use std::path::Path;
+
+use lvm_sys2::lvm::{CommandRetCode, Lvm};
+use rstest::rstest;
+
+#[rstest]
+#[case(&[])]
+#[case(&["sdb"])]
+#[case(&["/dev/"])]
+#[case(&["/tmp/disk.img"])]
+#[case(&["/dev/../tmp/disk.img"])]
+#[case(&["/dev/sdb", "/dev/sd c"])]
+#[case(&["/dev/sdb,/dev/sdc"])]
+fn invalid_devices(#[case] devices: &[&str]) {
+    let devices: Vec<_> = devices.iter().map(Path::new).collect();
+    assert_eq!(
+        Lvm::run_on_devices("pvs", &devices).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[rstest]
+#[case("/tmp/test.devices")]
+#[case("sub/test.devices")]
+#[case("/")]
+#[case("test file.devices")]
+fn invalid_devicesfile(#[case] path: &str) {
+    assert_eq!(
+        Lvm::run_with_devicesfile("pvs", Path::new(path)).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
@@ -0,0 +1,104 @@
+use std::{ffi::CStr, mem::offset_of, ptr};
+
+use lvm_sys2::{dm_list, dm_str_list, dmlist::DmList};
+
+/// circular list of `dm_str_list`s as libdevmapper builds it
+fn link(head: &mut dm_list, items: &mut [dm_str_list]) {
+    let head_ptr: *mut dm_list = head;
+    let mut prev = head_ptr;
+    for item in items.iter_mut() {
+        let link: *mut dm_list = &mut item.list;
+        // SAFETY: all pointers are into `head` and `items`, which outlive the list
+        unsafe {
+            (*prev).n = link;
+            (*link).p = prev;
+        }
+        prev = link;
+    }
+    // SAFETY: see above
+    unsafe {
+        (*prev).n = head_ptr;
+    }
+    head.p = prev;
+}
+
+fn empty_link() -> dm_list {
+    dm_list {
+        n: ptr::null_mut(),
+        p: ptr::null_mut(),
+    }
+}
+
+fn strings(list: &DmList<dm_str_list>) -> Vec<String> {
+    list.iter()
+        .map(|item| {
+            // SAFETY: the test puts C string literals there
+            unsafe { CStr::from_ptr(item.str) }
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+}
+
+#[test]
+fn iterates_in_order() {
+    let names = [c"a", c"b", c"c"];
+    let mut items: Vec<_> = names
+        .iter()
+        .map(|name| dm_str_list {
+            list: empty_link(),
+            str: name.as_ptr(),
+        })
+        .collect();
+    let mut head = empty_link();
+    link(&mut head, &mut items);
+    // SAFETY: the list is linked above
+    let list = unsafe { DmList::new(&head, offset_of!(dm_str_list, list)) };
+    assert_eq!(strings(&list), ["a", "b", "c"]);
+    assert_eq!((&list).into_iter().count(), 3);
+}
+
+#[test]
+fn empty_list() {
+    let mut head = empty_link();
+    link(&mut head, &mut []);
+    // SAFETY: the empty head points to itself
+    let list = unsafe { DmList::<dm_str_list>::new(&head, offset_of!(dm_str_list, list)) };
+    assert!(list.iter().next().is_none());
+}
+
+/// elements with the link not at the start
+#[repr(C)]
+struct Tagged {
+    tag: u64,
+    list: dm_list,
+}
+
+#[test]
+fn link_at_offset() {
+    let mut items = [
+        Tagged {
+            tag: 1,
+            list: empty_link(),
+        },
+        Tagged {
+            tag: 2,
+            list: empty_link(),
+        },
+    ];
+    let mut head = empty_link();
+    let head_ptr: *mut dm_list = &mut head;
+    let first: *mut dm_list = &mut items[0].list;
+    let second: *mut dm_list = &mut items[1].list;
+    // SAFETY: all pointers are into `head` and `items`
+    unsafe {
+        (*head_ptr).n = first;
+        (*first).n = second;
+        (*second).n = head_ptr;
+        (*head_ptr).p = second;
+    }
+    // SAFETY: the list is linked above
+    let list = unsafe { DmList::<Tagged>::new(&head, offset_of!(Tagged, list)) };
+    let tags: Vec<_> = list.iter().map(|item| item.tag).collect();
+    assert_eq!(tags, [1, 2]);
+}
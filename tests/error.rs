@@ -0,0 +1,42 @@
+use lvm_sys2::{error::LvmError, lvm::CommandRetCode};
+use rstest::rstest;
+
+#[rstest]
+#[case(
+    "Can't open /dev/sdb exclusively.  Mounted filesystem?",
+    LvmError::DeviceBusy
+)]
+#[case("Cannot use /dev/sdz: device not found", LvmError::DeviceNotFound)]
+#[case("Volume group \"vg0\" not found", LvmError::VgNotFound)]
+#[case("Failed to find logical volume \"vg0/lv0\"", LvmError::LvNotFound)]
+#[case(
+    "Physical volume '/dev/sdb' is already in volume group 'vg0'",
+    LvmError::PvInUse
+)]
+#[case(
+    "Volume group \"vg0\" has insufficient free space (10 extents): 256 required.",
+    LvmError::InsufficientFreeSpace
+)]
+#[case("Can't get lock for vg0.", LvmError::LockFailed)]
+fn known_errors(#[case] message: &str, #[case] variant: fn(String) -> LvmError) {
+    let messages = vec![
+        "  Cannot process volume group vg0".to_string(),
+        message.to_string(),
+    ];
+    assert_eq!(
+        LvmError::classify(CommandRetCode::ProcessingFailed, messages),
+        variant(message.to_string())
+    );
+}
+
+#[test]
+fn unknown_error() {
+    let messages = vec!["something went wrong".to_string()];
+    assert_eq!(
+        LvmError::classify(CommandRetCode::ProcessingFailed, messages.clone()),
+        LvmError::Other {
+            code: CommandRetCode::ProcessingFailed,
+            messages
+        }
+    );
+}
@@ -0,0 +1,13 @@
+use lvm_sys2::lvm::{CommandRetCode, Lvm};
+use rstest::rstest;
+
+#[rstest]
+#[case("")]
+#[case("vg 0")]
+#[case("vg'0")]
+fn invalid_vg_is_rejected(#[case] vg: &str) {
+    assert_eq!(
+        Lvm::lvs_historical(vg),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
@@ -0,0 +1,29 @@
+use lvm_sys2::{
+    lock::LockMode,
+    lvm::{CommandRetCode, Lvm},
+};
+use rstest::rstest;
+
+#[test]
+fn invalid_vg_is_rejected() {
+    assert_eq!(
+        Lvm::vg_lock("vg 0", LockMode::Shared).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    assert_eq!(
+        Lvm::vg_unlock("vg/0").unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[rstest]
+#[case("")]
+#[case("skip")]
+#[case("skipgl")]
+#[case("force --yes")]
+fn unknown_lockopt_is_rejected(#[case] lockopt: &str) {
+    assert_eq!(
+        Lvm::run_with_lockopt("vgs", lockopt).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
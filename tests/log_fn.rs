@@ -0,0 +1,13 @@
+// own test binary: the log function is process-wide, switching it would break concurrent tests
+use lvm_sys2::lvm::{CommandRetCode, Lvm};
+
+#[test]
+fn switching_log_fn() {
+    unsafe { Lvm::use_default_log_fn() };
+    assert!(matches!(
+        Lvm::run("vgs"),
+        Err(CommandRetCode::ClosurePanicked(_))
+    ));
+    unsafe { Lvm::use_internal_log_fn() };
+    assert!(Lvm::run("vgs").is_ok());
+}
@@ -0,0 +1,51 @@
+use lvm_sys2::lvm::{LogLevel, ParseLogLevelError};
+use rstest::rstest;
+
+#[rstest]
+#[case(LogLevel::FATAL, "FATAL", 2)]
+#[case(LogLevel::ERROR, "ERROR", 3)]
+#[case(LogLevel::PRINT, "PRINT", 4)]
+#[case(LogLevel::VERBOSE, "VERBOSE", 5)]
+#[case(LogLevel::VERY_VERBOSE, "VERY_VERBOSE", 6)]
+#[case(LogLevel::DEBUG, "DEBUG", 7)]
+#[case(LogLevel::UNKNOWN, "UNKNOWN", 5)]
+fn display_and_priority(#[case] level: LogLevel, #[case] name: &str, #[case] priority: u8) {
+    assert_eq!(level.to_string(), name);
+    assert_eq!(level.to_syslog_priority(), priority);
+}
+
+#[test]
+fn native_levels_are_syslog_priorities() {
+    for code in 2..=7 {
+        assert_eq!(i32::from(LogLevel::from(code).to_syslog_priority()), code);
+    }
+}
+
+#[rstest]
+#[case("VERBOSE", LogLevel::VERBOSE)]
+#[case("verbose", LogLevel::VERBOSE)]
+#[case("Very_Verbose", LogLevel::VERY_VERBOSE)]
+#[case(" error ", LogLevel::ERROR)]
+#[case("unknown", LogLevel::UNKNOWN)]
+fn parse(#[case] input: &str, #[case] expected: LogLevel) {
+    assert_eq!(input.parse::<LogLevel>(), Ok(expected));
+}
+
+#[rstest]
+#[case("")]
+#[case("WARNING")]
+#[case("VERY VERBOSE")]
+fn parse_invalid(#[case] input: &str) {
+    assert_eq!(
+        input.parse::<LogLevel>(),
+        Err(ParseLogLevelError(input.to_string()))
+    );
+}
+
+#[test]
+fn display_parse_round_trip() {
+    for code in 0..=8 {
+        let level = LogLevel::from(code);
+        assert_eq!(level.to_string().parse::<LogLevel>(), Ok(level));
+    }
+}
@@ -0,0 +1,163 @@
+use lvm_sys2::{
+    error::NameValidationError,
+    lv::{LvChangeOptions, LvCreateOptions, LvType, RaidLvOptions, lv_validate_name},
+    lvm::{CommandRetCode, Lvm},
+    units::ByteSize,
+};
+use rstest::rstest;
+
+#[rstest]
+#[case(
+    LvCreateOptions::new("vg0").name("data").size(ByteSize(1 << 30)),
+    "lvcreate -L 1073741824b -n 'data' 'vg0'"
+)]
+#[case(
+    LvCreateOptions::new("vg+0").name("a+b").extents(100).lv_type(LvType::Raid1).tag("web"),
+    "lvcreate --type raid1 -l 100 -n 'a+b' --addtag 'web' 'vg+0'"
+)]
+#[case(
+    LvCreateOptions::new("vg0").name("pool").size(ByteSize(4096)).thin(),
+    "lvcreate -L 4096b -n 'pool' -T 'vg0'"
+)]
+#[case(
+    LvCreateOptions::new("vg0").name("snap").size(ByteSize(4096)).snapshot("data"),
+    "lvcreate -L 4096b -n 'snap' -s 'vg0/data'"
+)]
+#[case(
+    LvCreateOptions::new("vg0").name("thin_snap").snapshot("thin"),
+    "lvcreate -n 'thin_snap' -s 'vg0/thin'"
+)]
+#[case(
+    LvCreateOptions::new("vg0").name("r1").size(ByteSize(4096)).lv_type(LvType::Raid1)
+        .raid(RaidLvOptions::new().mirrors(1).region_size(ByteSize(1 << 20))),
+    "lvcreate --type raid1 -m 1 -R 1048576b -L 4096b -n 'r1' 'vg0'"
+)]
+#[case(
+    LvCreateOptions::new("vg0").name("r6").extents(10).lv_type(LvType::Raid6)
+        .raid(RaidLvOptions::new().stripes(3).stripe_size(ByteSize(64 << 10))),
+    "lvcreate --type raid6 -i 3 -I 65536b -l 10 -n 'r6' 'vg0'"
+)]
+fn lvcreate_command(#[case] options: LvCreateOptions, #[case] expected: &str) {
+    assert_eq!(options.command().unwrap(), expected);
+}
+
+#[rstest]
+#[case(LvCreateOptions::new("vg0"))]
+#[case(LvCreateOptions::new("vg0").size(ByteSize(0)))]
+#[case(LvCreateOptions::new("vg0").extents(0))]
+#[case(LvCreateOptions::new("vg0").size(ByteSize(1)).extents(1))]
+#[case(LvCreateOptions::new("vg/0").size(ByteSize(1)))]
+#[case(LvCreateOptions::new("vg0").size(ByteSize(1)).name("snapshot0"))]
+#[case(LvCreateOptions::new("vg0").size(ByteSize(1)).name("lv_tmeta"))]
+#[case(LvCreateOptions::new("vg0").size(ByteSize(1)).tag("bad tag"))]
+#[case(LvCreateOptions::new("vg0").size(ByteSize(1)).lv_type(LvType::Raid5).raid(RaidLvOptions::new().stripes(1)))]
+#[case(LvCreateOptions::new("vg0").size(ByteSize(1)).lv_type(LvType::Raid6).raid(RaidLvOptions::new().stripes(2)))]
+#[case(LvCreateOptions::new("vg0").size(ByteSize(1)).raid(RaidLvOptions::new().stripes(0)))]
+#[case(LvCreateOptions::new("vg0").size(ByteSize(1)).raid(RaidLvOptions::new().region_size(ByteSize(0))))]
+fn lvcreate_invalid(#[case] options: LvCreateOptions) {
+    assert!(matches!(
+        options.command(),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+}
+
+#[test]
+fn lvchange_command() {
+    let options = LvChangeOptions::new()
+        .activate(false)
+        .read_only(true)
+        .del_tag("old");
+    assert_eq!(
+        options.command("vg0", "data").unwrap(),
+        "lvchange -an -p r --deltag 'old' 'vg0/data'"
+    );
+    assert!(matches!(
+        LvChangeOptions::new().command("vg0", "data"),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+}
+
+#[test]
+fn lvresize_zero_is_rejected() {
+    assert!(matches!(
+        Lvm::lv().lvresize("vg0", "data", ByteSize(0)),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+    assert_eq!(
+        Lvm::lvextend_by("vg0", "data", ByteSize(0)).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    assert_eq!(
+        Lvm::lvextend_to("vg0", "data", ByteSize(0)).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    assert_eq!(
+        Lvm::lvreduce("vg0", "data", ByteSize(0), true).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[rstest]
+#[case("vg/0", "lv")]
+#[case("vg0", "a/b")]
+fn activation_rejects_slashes(#[case] vg: &str, #[case] lv: &str) {
+    assert_eq!(
+        Lvm::activate_lv(vg, lv).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    assert_eq!(
+        Lvm::deactivate_lv(vg, lv).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    assert_eq!(
+        Lvm::lv_is_active(vg, lv).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    assert_eq!(
+        Lvm::wipe_lv(vg, lv).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    assert_eq!(
+        Lvm::lv_is_zeroed(vg, lv).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[test]
+fn activate_scoped_rejects_invalid_names() {
+    assert_eq!(
+        Lvm::activate_scoped("vg0", "a/b").unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[test]
+fn raid_repair_rejects_missing_device() {
+    assert!(matches!(
+        Lvm::lvconvert_raid_repair("vg0", "r1", std::path::Path::new("/dev/lvm-sys2-missing")),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+}
+
+#[rstest]
+#[case(0, &[])]
+#[case(5, &[])]
+#[case(1, &["/dev/lvm-sys2-missing"])]
+fn lvconvert_to_mirror_invalid(#[case] mirrors: u8, #[case] pvs: &[&str]) {
+    let pvs: Vec<_> = pvs.iter().map(std::path::Path::new).collect();
+    assert_eq!(
+        Lvm::lvconvert_to_mirror("vg0", "data", mirrors, &pvs).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[rstest]
+#[case("data", Ok(()))]
+#[case("my_snapshot", Ok(()))]
+#[case("snapshot0", Err(NameValidationError::ReservedWord("snapshot")))]
+#[case("pvmove", Err(NameValidationError::ReservedWord("pvmove")))]
+#[case("pool_tmeta", Err(NameValidationError::ReservedWord("_tmeta")))]
+#[case("da'ta", Err(NameValidationError::InvalidChars("'".to_string())))]
+fn lv_name_validation(#[case] name: &str, #[case] expected: Result<(), NameValidationError>) {
+    assert_eq!(lv_validate_name(name), expected);
+}
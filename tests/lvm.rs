@@ -1,10 +1,24 @@
-use lvm_sys2::lvm::Lvm;
+use lvm_sys2::{
+    lvm::{
+        CommandRetCode, LogLevel, Lvm, LvmInitOptions, LvmLogSink, ReportFormat, VerbosityLevel,
+    },
+    retry::RetryPolicy,
+    version::LvmVersion,
+};
 use rstest::rstest;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
 
 /// this test doesn't require any
 #[test]
 fn basic_init_deinit() {
     Lvm::acquire_and(|_lvm| Ok("some".to_string())).expect("lock or init failed");
+    assert!(Lvm::is_available());
 }
 
 #[test]
@@ -27,3 +41,262 @@ fn test_run_ro_command(#[case] cmd: &str) {
     let _res = Lvm::run(cmd).unwrap();
     // pvremove
 }
+
+#[test]
+fn run_with_timeout() {
+    Lvm::run_with_timeout("vgs", Duration::from_secs(30)).unwrap();
+}
+
+#[rstest]
+#[case("pvs")]
+#[case("vgs")]
+#[case("lvs")]
+fn test_run_raw_is_json(#[case] cmd: &str) {
+    let raw = Lvm::run_raw(cmd).unwrap();
+    serde_json::from_str::<serde_json::Value>(&raw).expect("output is not a valid JSON");
+}
+
+struct CountingSink(AtomicUsize);
+
+impl LvmLogSink for CountingSink {
+    fn log(&self, _level: LogLevel, _file: &str, _line: i32, _message: &str) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn log_sink_receives_messages() {
+    let sink = Arc::new(CountingSink(AtomicUsize::new(0)));
+    Lvm::set_log_sink(sink.clone());
+    Lvm::run("vgs").unwrap();
+    assert!(sink.0.load(Ordering::Relaxed) > 0);
+}
+
+#[test]
+fn run_with_retry() {
+    Lvm::run_with_retry("vgs", RetryPolicy::default()).unwrap();
+}
+
+#[test]
+fn run_multiple() {
+    let results = Lvm::run_multiple(&["pvs", "no-such-command", "lvs"]);
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+}
+
+#[test]
+fn duplicate_report_format_is_rejected() {
+    assert_eq!(
+        Lvm::set_default_flags("--units b --reportformat basic"),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
+
+#[rstest]
+#[case(&[])]
+#[case(&[""])]
+#[case(&["pv_name", "pv_size,vg_name"])]
+#[case(&["pv name"])]
+fn invalid_fields_are_rejected(#[case] fields: &[&str]) {
+    assert_eq!(
+        Lvm::run_fields("pvs", fields),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
+
+#[rstest]
+#[case(&[])]
+#[case(&[""])]
+#[case(&["-"])]
+#[case(&["--lv_size"])]
+#[case(&["lv_name,lv_size"])]
+#[case(&["LV_NAME"])]
+#[case(&["lv name"])]
+fn invalid_sort_fields_are_rejected(#[case] fields: &[&str]) {
+    assert_eq!(
+        Lvm::run_sorted("lvs", fields),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
+
+#[test]
+fn run_sorted() {
+    let res = Lvm::run_sorted("lvs", &["vg_name", "-lv_size"]).unwrap();
+    assert!(res.contains_key("report"));
+}
+
+#[test]
+fn run_fields() {
+    let res = Lvm::run_fields("vgs", &["vg_name", "vg_free"]).unwrap();
+    assert!(res.contains_key("report"));
+}
+
+#[test]
+fn select_without_matches() {
+    assert!(
+        Lvm::pvs_select("pv_name=/dev/lvm-sys2-missing")
+            .unwrap()
+            .is_empty()
+    );
+    assert!(Lvm::lvs_select("lv_size > 1p").unwrap().is_empty());
+}
+
+#[rstest]
+#[case("vg_name=a; rm -rf /")]
+#[case("vg_name=$(id)")]
+#[case("vg_name='a'")]
+#[case("")]
+fn select_injection_is_rejected(#[case] filter: &str) {
+    assert_eq!(
+        Lvm::vgs_select(filter),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
+
+#[test]
+fn version() {
+    assert!(Lvm::version().unwrap() >= LvmVersion::new(2, 2, 0));
+}
+
+#[test]
+fn display_missing_objects() {
+    assert!(Lvm::pvdisplay(std::path::Path::new("/dev/no-such-device")).is_err());
+    assert!(Lvm::vgdisplay("no-such-vg").is_err());
+    assert!(Lvm::lvdisplay("no-such-vg", "no-such-lv").is_err());
+    assert_eq!(
+        Lvm::vgdisplay("bad vg").unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[test]
+fn reset() {
+    assert!(Lvm::is_available());
+    unsafe { Lvm::reset() };
+    assert!(Lvm::is_available());
+    Lvm::run("vgs").unwrap();
+}
+
+#[test]
+fn scan() {
+    Lvm::vgscan(false).unwrap();
+    Lvm::pvscan(false, false).unwrap();
+}
+
+#[test]
+fn run_with_env() {
+    unsafe { Lvm::run_with_env("vgs", &[("LVM_SUPPRESS_FD_WARNINGS", "1")]) }.unwrap();
+    assert!(std::env::var_os("LVM_SUPPRESS_FD_WARNINGS").is_none());
+}
+
+#[rstest]
+#[case("", "1")]
+#[case("A=B", "1")]
+#[case("A", "1\0")]
+fn run_with_env_rejects_invalid_vars(#[case] name: &str, #[case] value: &str) {
+    assert_eq!(
+        unsafe { Lvm::run_with_env("vgs", &[(name, value)]) }.unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[test]
+fn run_with_config() {
+    let path = std::env::temp_dir().join("lvm-sys2-test.conf");
+    std::fs::write(&path, "global {\n\tsuffix=1\n}\n").unwrap();
+    Lvm::run_with_config("vgs", &path).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn run_quiet() {
+    Lvm::run_quiet("vgscan").unwrap();
+    assert_eq!(
+        Lvm::run_quiet("no-such-command").unwrap_err(),
+        CommandRetCode::NoSuchCommand
+    );
+}
+
+#[test]
+fn run_collecting_logs() {
+    let (report, _logs) = Lvm::run_collecting_logs("vgs").unwrap();
+    assert!(report.contains_key("report"));
+}
+
+#[test]
+fn run_with_verbosity() {
+    let (report, logs) = Lvm::run_with_verbosity("vgs", VerbosityLevel::VeryVerbose).unwrap();
+    assert!(report.contains_key("report"));
+    assert!(!logs.is_empty());
+}
+
+#[test]
+fn run_format() {
+    let vgs = Lvm::vgs().unwrap().len();
+    let basic = Lvm::run_format("vgs", ReportFormat::Basic).unwrap();
+    assert!(!basic.contains("\"report\""));
+    // a heading and a line per VG
+    assert_eq!(basic.lines().count(), vgs + 1);
+    let columns = Lvm::run_format("vgs -o vg_name,vg_size", ReportFormat::Columns).unwrap();
+    assert_eq!(columns.lines().count(), vgs);
+    assert!(columns.lines().all(|line| line.contains(':')));
+    assert!(
+        Lvm::run_format("vgs", ReportFormat::Json)
+            .unwrap()
+            .contains("\"report\"")
+    );
+}
+
+#[test]
+fn acquire_and_catches_panics() {
+    assert_eq!(
+        Lvm::acquire_and(|_lvm| -> Result<(), CommandRetCode> { panic!("boom") }).unwrap_err(),
+        CommandRetCode::ClosurePanicked("boom".to_string())
+    );
+    // the lock isn't poisoned
+    Lvm::run("vgs").unwrap();
+}
+
+#[test]
+fn init_with_after_init() {
+    // initializes the singleton or caches the init failure
+    let _ = Lvm::is_available();
+    assert_eq!(
+        unsafe { Lvm::init_with(LvmInitOptions::new().system_dir("/etc/lvm")) }.unwrap_err(),
+        CommandRetCode::AlreadyInitialized
+    );
+}
+
+#[rstest]
+#[case(LvmInitOptions::new().config_profile(""))]
+#[case(LvmInitOptions::new().config_profile("../profile"))]
+#[case(LvmInitOptions::new().system_dir("/etc/lvm\0"))]
+fn init_with_invalid_options(#[case] options: LvmInitOptions) {
+    assert_eq!(
+        unsafe { Lvm::init_with(options) }.unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[test]
+fn last_error_message() {
+    assert_eq!(
+        Lvm::run("vgs lvm-sys2-no-such-vg").unwrap_err(),
+        CommandRetCode::ProcessingFailed
+    );
+    assert!(Lvm::last_error_message().unwrap().contains("not found"));
+    assert_eq!(Lvm::last_error_message(), None);
+    Lvm::run("vgs").unwrap();
+    assert_eq!(Lvm::last_error_message(), None);
+}
+
+#[test]
+fn run_with_retcode() {
+    let (res, code) = Lvm::run_with_retcode("vgs lvm-sys2-no-such-vg");
+    assert_eq!(res.unwrap_err(), CommandRetCode::ProcessingFailed);
+    assert_eq!(code, 5);
+    assert_eq!(Lvm::run_with_retcode("vgs").1, 1);
+    assert_eq!(Lvm::run_with_retcode("vgs\0").1, 0);
+}
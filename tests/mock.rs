@@ -0,0 +1,807 @@
+#![cfg(feature = "test-utils")]
+
+use std::path::Path;
+
+use lvm_sys2::{
+    cache::CachePolicy,
+    history::HistoricalLvInfo,
+    lock::LockMode,
+    lvm::{CommandRetCode, Lvm, VerbosityLevel},
+    mock::{LVS_FIXTURE, MockLvm, PVS_FIXTURE, VGS_FIXTURE},
+    monitor::DmEvent,
+    snapshot::OrphanReason,
+    units::ByteSize,
+};
+use rstest::rstest;
+use serde_json::{Value, json};
+
+#[test]
+fn standard_fixtures() {
+    let _mock = MockLvm::with_standard_fixtures();
+    let pvs = Lvm::pvs().unwrap();
+    assert_eq!(pvs[0].pv_name, "/dev/sdb");
+    assert_eq!(pvs[0].vg_name.as_deref(), Some("vg0"));
+    let vgs = Lvm::vgs().unwrap();
+    assert_eq!(vgs[0].vg_name, "vg0");
+    assert_eq!(vgs[0].lv_count, 1);
+    let lvs = Lvm::lvs().unwrap();
+    assert_eq!(lvs[0].lv_size, ByteSize::from_gib(4));
+    assert!(lvs[0].is_active());
+}
+
+#[rstest]
+#[case(Ok(String::new()), Ok(()))]
+#[case(
+    Err(CommandRetCode::ProcessingFailed),
+    Err(CommandRetCode::ProcessingFailed)
+)]
+fn canned_response(
+    #[case] response: Result<String, CommandRetCode>,
+    #[case] expected: Result<(), CommandRetCode>,
+) {
+    let _mock = MockLvm::new().respond("pvremove /dev/null", response);
+    assert_eq!(Lvm::pv().remove(Path::new("/dev/null")), expected);
+}
+
+#[test]
+fn unknown_command() {
+    let _mock = MockLvm::new();
+    assert_eq!(Lvm::run("pvs").unwrap_err(), CommandRetCode::NoSuchCommand);
+    assert_eq!(
+        Lvm::run_multiple(&["pvs", "vgs"]),
+        vec![
+            Err(CommandRetCode::NoSuchCommand),
+            Err(CommandRetCode::NoSuchCommand)
+        ]
+    );
+}
+
+#[test]
+// replaced mocks are only dropped, never read
+#[allow(unused_assignments)]
+fn reassigned_mock() {
+    let mut mock = MockLvm::new().respond("pvs", Ok(PVS_FIXTURE.to_string()));
+    assert!(Lvm::run("pvs").is_ok());
+    // the old mock is dropped after the new one is installed
+    mock = MockLvm::new().respond("vgs", Ok(VGS_FIXTURE.to_string()));
+    assert_eq!(Lvm::run("pvs").unwrap_err(), CommandRetCode::NoSuchCommand);
+    assert!(Lvm::run("vgs").is_ok());
+    // an outdated mock neither registers responses nor clears the current ones
+    let old = std::mem::replace(
+        &mut mock,
+        MockLvm::new().respond("lvs", Ok(LVS_FIXTURE.to_string())),
+    );
+    drop(old.respond("pvs", Ok(PVS_FIXTURE.to_string())));
+    assert_eq!(Lvm::run("pvs").unwrap_err(), CommandRetCode::NoSuchCommand);
+    assert!(Lvm::run("lvs").is_ok());
+}
+
+#[test]
+fn invalid_json() {
+    let _mock = MockLvm::new().respond("pvs", Ok("not a json".to_string()));
+    assert!(matches!(
+        Lvm::run("pvs"),
+        Err(CommandRetCode::JsonDeserializationFailed(_))
+    ));
+    assert_eq!(Lvm::run_raw("pvs").unwrap(), "not a json");
+}
+
+#[test]
+fn register_event_checks_lv_type() {
+    // the standard `vg0/data` is a linear LV
+    let _mock = MockLvm::new()
+        .respond("lvs 'vg0/data'", Ok(LVS_FIXTURE.to_string()))
+        .respond("lvchange --monitor y 'vg0/data'", Ok(String::new()));
+    assert_eq!(
+        Lvm::register_event("vg0", "data", &[DmEvent::SnapshotUsage]),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
+
+#[test]
+fn monitor_thin_pool() {
+    let _mock = MockLvm::new()
+        .respond(
+            "lvs 'vg0/data'",
+            Ok(LVS_FIXTURE.replace("-wi-a-----", "twi-a-tz--")),
+        )
+        .respond("lvchange --monitor y 'vg0/data'", Ok(String::new()))
+        .respond("lvchange --monitor n 'vg0/data'", Ok(String::new()));
+    Lvm::register_event("vg0", "data", &[DmEvent::ThinPoolSpaceUsage]).unwrap();
+    Lvm::unregister_event("vg0", "data").unwrap();
+}
+
+#[test]
+fn by_vg() {
+    let _mock = MockLvm::new()
+        .respond("pvs --select 'vg_name=vg0'", Ok(PVS_FIXTURE.to_string()))
+        .respond("lvs 'vg1'", Ok(r#"{"report":[{"lv":[]}]}"#.to_string()));
+    assert_eq!(Lvm::pvs_by_vg("vg0").unwrap().len(), 1);
+    assert!(Lvm::lvs_by_vg("vg1").unwrap().is_empty());
+    assert_eq!(
+        Lvm::lvs_by_vg("vg 1").unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[test]
+fn lvs_iter() {
+    let output = r#"{"report": [
+        {"lv": [
+            {"lv_name":"a", "vg_name":"vg0", "lv_attr":"-wi-a-----", "lv_size":"1.00g"},
+            {"lv_name":"b", "vg_name":"vg0", "lv_attr":"-wi-------", "lv_size":"2.00g"}
+        ]},
+        {"lv": []},
+        {"lv": [{"lv_name":"c", "vg_name":"vg1", "lv_attr":"-wi-a-----", "lv_size":"3.00g"}]}
+    ]}"#;
+    let _mock = MockLvm::new().respond("lvs", Ok(output.to_string()));
+    let names: Vec<_> = Lvm::lvs_all_vgs_iter()
+        .map(|lv| lv.unwrap().lv_name)
+        .collect();
+    assert_eq!(names, ["a", "b", "c"]);
+    assert_eq!(Lvm::lvs_all_vgs().unwrap().len(), 3);
+}
+
+#[rstest]
+#[case(Ok(r#"{"report": [{"lv": [{"lv_name":"a"}, {"lv_name":"b"}]}]}"#.to_string()))]
+#[case(Ok(r#"{"report": [{"lv": ["#.to_string()))]
+#[case(Err(CommandRetCode::ProcessingFailed))]
+fn lvs_iter_stops_on_failure(#[case] response: Result<String, CommandRetCode>) {
+    let _mock = MockLvm::new().respond("lvs", response);
+    let lvs: Vec<_> = Lvm::lvs_all_vgs_iter().collect();
+    assert_eq!(lvs.len(), 1);
+    assert!(lvs[0].is_err());
+}
+
+#[rstest]
+#[case(format!("PARTIAL MODE. Incomplete logical volumes will be processed.{VGS_FIXTURE}"), Some(CommandRetCode::PartialMode))]
+#[case(VGS_FIXTURE.to_string(), None)]
+fn partial_mode(#[case] output: String, #[case] advisory: Option<CommandRetCode>) {
+    let _mock = MockLvm::new().respond("vgs --partial", Ok(output));
+    let vgs = Lvm::vgs_partial().unwrap();
+    assert_eq!(vgs.output[0].vg_name, "vg0");
+    assert_eq!(vgs.advisory, advisory);
+}
+
+#[rstest]
+#[case(String::new(), json!({}))]
+#[case(
+    "TEST MODE: Metadata will NOT be updated and volumes will not be (de)activated.".to_string(),
+    json!({})
+)]
+#[case(
+    "TEST MODE: Metadata will NOT be updated and volumes will not be (de)activated.  Logical volume \"data\" created.".to_string(),
+    json!({"rust_logger": "Logical volume \"data\" created."})
+)]
+#[case(VGS_FIXTURE.to_string(), serde_json::from_str(VGS_FIXTURE).unwrap())]
+fn test_mode(#[case] output: String, #[case] expected: Value) {
+    let _mock = MockLvm::new().respond("lvcreate -L 1g -n data vg0 --test", Ok(output));
+    assert_eq!(
+        Value::Object(Lvm::run_in_test_mode("lvcreate -L 1g -n data vg0").unwrap()),
+        expected
+    );
+}
+
+#[test]
+fn split_into_existing_vg() {
+    let _mock = MockLvm::with_standard_fixtures()
+        .respond("vgsplit 'vg0' 'vg0' /dev/null", Ok(String::new()))
+        .respond("vgsplit 'vg0' 'vg1' /dev/null", Ok(String::new()));
+    assert_eq!(
+        Lvm::vg_split("vg0", "vg0", &[Path::new("/dev/null")]),
+        Err(CommandRetCode::InvalidParameters)
+    );
+    assert_eq!(
+        Lvm::vg_split("vg0", "vg1", &[Path::new("/dev/null")]),
+        Ok(())
+    );
+}
+
+#[test]
+fn lvconvert_mirror() {
+    let _mock = MockLvm::new()
+        .respond(
+            "lvconvert --yes -m 2 'vg0/data' /dev/null",
+            Ok(String::new()),
+        )
+        .respond("lvconvert --yes -m 0 'vg0/data'", Ok(String::new()));
+    assert_eq!(
+        Lvm::lvconvert_to_mirror("vg0", "data", 2, &[Path::new("/dev/null")]),
+        Ok(())
+    );
+    assert_eq!(Lvm::lvconvert_to_linear("vg0", "data"), Ok(()));
+}
+
+#[test]
+fn lockopt() {
+    let _mock = MockLvm::new()
+        .respond("vgs --lockopt skipvg", Ok(VGS_FIXTURE.to_string()))
+        .respond("vgs --lockopt skip", Ok(VGS_FIXTURE.to_string()));
+    assert!(Lvm::run_with_lockopt("vgs", "skipvg").is_ok());
+    assert!(Lvm::run_without_lock("vgs").is_ok());
+}
+
+#[test]
+fn readonly() {
+    let _mock = MockLvm::new()
+        .respond("pvs --readonly", Ok(PVS_FIXTURE.to_string()))
+        .respond("vgs --readonly", Ok(VGS_FIXTURE.to_string()))
+        .respond("lvs --readonly", Ok(LVS_FIXTURE.to_string()));
+    assert_eq!(Lvm::pvs_readonly().unwrap().len(), 1);
+    assert_eq!(Lvm::vgs_readonly().unwrap().len(), 1);
+    assert_eq!(Lvm::lvs_readonly().unwrap().len(), 1);
+    assert!(Lvm::run_readonly("vgs").unwrap().contains_key("report"));
+}
+
+#[test]
+fn list_snapshots() {
+    let output = r#"{"report":[{"lv":[
+        {"lv_name":"data", "vg_name":"vg0", "lv_attr":"owi-a-s---", "lv_size":"4.00g", "origin":""},
+        {"lv_name":"snap", "vg_name":"vg0", "lv_attr":"swi-a-s---", "lv_size":"1.00g", "origin":"data"},
+        {"lv_name":"full", "vg_name":"vg0", "lv_attr":"Swi-I-s---", "lv_size":"1.00g", "origin":"data"}
+    ]}]}"#;
+    let _mock = MockLvm::new().respond("lvs 'vg0'", Ok(output.to_string()));
+    let names: Vec<_> = Lvm::snapshot()
+        .list_snapshots("vg0")
+        .unwrap()
+        .into_iter()
+        .map(|lv| lv.lv_name)
+        .collect();
+    assert_eq!(names, ["snap", "full"]);
+    assert_eq!(
+        Lvm::snapshot().list_snapshots("vg 0"),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
+
+#[rstest]
+#[case("swi-a-s---", "42.50", Ok(42.5))]
+#[case("Swi-I-s---", "100.00", Err(CommandRetCode::SnapshotInvalid))]
+#[case("-wi-a-----", "", Err(CommandRetCode::SnapshotInvalid))]
+fn snapshot_usage(
+    #[case] attr: &str,
+    #[case] percent: &str,
+    #[case] expected: Result<f64, CommandRetCode>,
+) {
+    let output = format!(
+        r#"{{"report":[{{"lv":[{{"lv_name":"snap", "vg_name":"vg0", "lv_attr":"{attr}", "lv_size":"1.00g", "snap_percent":"{percent}"}}]}}]}}"#
+    );
+    let _mock = MockLvm::new().respond(
+        "lvs -o +snap_percent --select 'lv_name=snap' 'vg0'",
+        Ok(output),
+    );
+    assert_eq!(Lvm::snapshot_usage("vg0", "snap"), expected);
+}
+
+#[rstest]
+#[case("<6.00g", Ok(()))]
+#[case("0 ", Err(CommandRetCode::InsufficientFreeSpace))]
+fn lvextend_to_fill_vg(#[case] vg_free: &str, #[case] expected: Result<(), CommandRetCode>) {
+    let vgs = VGS_FIXTURE.replace(
+        r#""vg_free":"<6.00g""#,
+        &format!(r#""vg_free":"{vg_free}""#),
+    );
+    let _mock = MockLvm::new()
+        .respond("vgs 'vg0'", Ok(vgs))
+        .respond("lvextend -l +100%FREE 'vg0/data'", Ok(String::new()));
+    assert_eq!(Lvm::lvextend_to_fill_vg("vg0", "data"), expected);
+}
+
+#[test]
+fn run_sorted() {
+    let _mock = MockLvm::new().respond("lvs --sort vg_name,-lv_size", Ok(LVS_FIXTURE.to_string()));
+    assert!(Lvm::run_sorted("lvs", &["vg_name", "-lv_size"]).is_ok());
+}
+
+#[rstest]
+#[case(0, 2, &["a", "b"], true)]
+#[case(1, 2, &["c", "d"], true)]
+#[case(2, 2, &["e"], false)]
+#[case(3, 2, &[], false)]
+#[case(0, 5, &["a", "b", "c", "d", "e"], false)]
+#[case(0, 10, &["a", "b", "c", "d", "e"], false)]
+#[case(usize::MAX, usize::MAX, &[], false)]
+fn run_paginated(
+    #[case] page: usize,
+    #[case] page_size: usize,
+    #[case] names: &[&str],
+    #[case] has_more: bool,
+) {
+    let output = r#"{"report":[{"lv":[{"lv_name":"a"},{"lv_name":"b"},{"lv_name":"c"},{"lv_name":"d"},{"lv_name":"e"}]}]}"#;
+    let _mock = MockLvm::new().respond("lvs -o lv_name", Ok(output.to_string()));
+    let result = Lvm::run_paginated("lvs -o lv_name", page, page_size).unwrap();
+    let items: Vec<_> = result.items.iter().map(|row| &row["lv_name"]).collect();
+    assert_eq!(items, names);
+    assert_eq!((result.page, result.page_size), (page, page_size));
+    assert_eq!(result.has_more, has_more);
+}
+
+#[test]
+fn lvs_by_tag() {
+    let _mock = MockLvm::new().respond(
+        "lvs --select 'lv_tags={backup}'",
+        Ok(LVS_FIXTURE.to_string()),
+    );
+    assert_eq!(Lvm::lvs_by_tag("backup").unwrap()[0].lv_name, "data");
+}
+
+#[rstest]
+#[case("", &[])]
+#[case("backup", &["backup"])]
+#[case("backup,daily", &["backup", "daily"])]
+#[case(" backup , daily ,", &["backup", "daily"])]
+fn lv_tags_are_parsed(#[case] tags: &str, #[case] expected: &[&str]) {
+    let _mock = MockLvm::new().respond(
+        "lvs -o lv_tags 'vg0/data'",
+        Ok(format!(
+            r#"{{"report":[{{"lv":[{{"lv_tags":"{tags}"}}]}}]}}"#
+        )),
+    );
+    assert_eq!(Lvm::lv_tags("vg0", "data").unwrap(), expected);
+}
+
+#[test]
+fn lvs_with_tag_round_trip() {
+    let select = "lvs --select 'lv_tags={backup}'";
+    let mock = MockLvm::new()
+        .respond(select, Ok(r#"{"report":[{"lv":[]}]}"#.to_string()))
+        .respond("lvchange --addtag 'backup' 'vg0/data'", Ok(String::new()))
+        .respond("lvchange --deltag 'backup' 'vg0/data'", Ok(String::new()));
+    assert!(Lvm::lvs_with_tag("backup").unwrap().is_empty());
+
+    Lvm::lv_tags_add("vg0", "data", &["backup"]).unwrap();
+    let mock = mock.respond(select, Ok(LVS_FIXTURE.to_string()));
+    assert_eq!(Lvm::lvs_with_tag("backup").unwrap()[0].lv_name, "data");
+
+    Lvm::lv_tags_remove("vg0", "data", &["backup"]).unwrap();
+    let _mock = mock.respond(select, Ok(r#"{"report":[{"lv":[]}]}"#.to_string()));
+    assert!(Lvm::lvs_with_tag("backup").unwrap().is_empty());
+}
+
+#[test]
+fn lv_tags_round_trip() {
+    let tags = |tags: &str| {
+        Ok(format!(
+            r#"{{"report":[{{"lv":[{{"lv_tags":"{tags}"}}]}}]}}"#
+        ))
+    };
+    let mock = MockLvm::new()
+        .respond("lvs -o lv_tags 'vg0/data'", tags(""))
+        .respond("lvchange --addtag 'backup' 'vg0/data'", Ok(String::new()))
+        .respond("lvchange --deltag 'backup' 'vg0/data'", Ok(String::new()));
+    assert!(Lvm::lv_tags("vg0", "data").unwrap().is_empty());
+
+    Lvm::lv_tags_add("vg0", "data", &["backup"]).unwrap();
+    let mock = mock.respond("lvs -o lv_tags 'vg0/data'", tags("backup,daily"));
+    assert_eq!(Lvm::lv_tags("vg0", "data").unwrap(), ["backup", "daily"]);
+
+    Lvm::lv_tags_remove("vg0", "data", &["backup"]).unwrap();
+    let _mock = mock.respond("lvs -o lv_tags 'vg0/data'", tags("daily"));
+    assert_eq!(Lvm::lv_tags("vg0", "data").unwrap(), ["daily"]);
+}
+
+#[test]
+fn vg_tags() {
+    let _mock = MockLvm::new()
+        .respond(
+            "vgs -o vg_tags 'vg0'",
+            Ok(r#"{"report":[{"vg":[{"vg_tags":"prod"}]}]}"#.to_string()),
+        )
+        .respond(
+            "vgs -o vg_tags 'vg1'",
+            Ok(r#"{"report":[{"vg":[]}]}"#.to_string()),
+        );
+    assert_eq!(Lvm::vg_tags("vg0").unwrap(), ["prod"]);
+    assert!(matches!(
+        Lvm::vg_tags("vg1"),
+        Err(CommandRetCode::UnexpectedOutput(_))
+    ));
+}
+
+#[test]
+fn vg_sizes() {
+    let _mock = MockLvm::new()
+        .respond(
+            "vgs -o vg_free 'vg0'",
+            Ok(r#"{"report":[{"vg":[{"vg_free":"<6.00g"}]}]}"#.to_string()),
+        )
+        .respond(
+            "vgs -o vg_size 'vg0'",
+            Ok(r#"{"report":[{"vg":[{"vg_size":"10.00g"}]}]}"#.to_string()),
+        )
+        .respond(
+            "vgs -o vg_extent_size 'vg0'",
+            Ok(r#"{"report":[{"vg":[{"vg_extent_size":"4.00m"}]}]}"#.to_string()),
+        );
+    assert_eq!(Lvm::vg_free_space("vg0"), Ok(ByteSize::from_gib(6)));
+    assert_eq!(Lvm::vg_size("vg0"), Ok(ByteSize::from_gib(10)));
+    assert_eq!(Lvm::vg_extent_size("vg0"), Ok(ByteSize::from_mib(4)));
+}
+
+#[test]
+fn vg_size_of_empty_report() {
+    let _mock = MockLvm::new().respond(
+        "vgs -o vg_size 'vg0'",
+        Ok(r#"{"report":[{"vg":[]}]}"#.to_string()),
+    );
+    assert_eq!(
+        Lvm::vg_size("vg0"),
+        Err(CommandRetCode::UnexpectedOutput(
+            "vgs -o vg_size 'vg0'".to_string()
+        ))
+    );
+}
+
+#[test]
+fn pvs_free() {
+    let output = r#"{"report":[{"pv":[
+        {"pv_name":"/dev/sda","vg_name":"vg0","pv_fmt":"lvm2","pv_attr":"a--","pv_size":"1.00g","pv_free":"0 "},
+        {"pv_name":"/dev/sdb","vg_name":"vg0","pv_fmt":"lvm2","pv_attr":"a--","pv_size":"10.00g","pv_free":"6.00g"},
+        {"pv_name":"/dev/sdc","vg_name":"","pv_fmt":"lvm2","pv_attr":"---","pv_size":"2.00g","pv_free":"2.00g"}
+    ]}]}"#;
+    let _mock = MockLvm::new().respond("pvs", Ok(output.to_string()));
+    let pvs = Lvm::pvs_free().unwrap();
+    let names: Vec<_> = pvs.iter().map(|p| p.pv.pv_name.as_str()).collect();
+    assert_eq!(names, ["/dev/sdb", "/dev/sdc", "/dev/sda"]);
+    assert_eq!(pvs[0].free, ByteSize::from_gib(6));
+    assert_eq!(pvs[2].free, ByteSize(0));
+}
+
+#[rstest]
+#[case("/report/0/vg/0/vg_name", Ok(json!("vg0")))]
+#[case("/report/0/vg/0/lv_count", Ok(json!("1")))]
+#[case("/report/0/vg/1", Err(CommandRetCode::JsonPointerNotFound("/report/0/vg/1".to_string())))]
+#[case("report", Err(CommandRetCode::JsonPointerNotFound("report".to_string())))]
+fn run_json_pointer(#[case] pointer: &str, #[case] expected: Result<Value, CommandRetCode>) {
+    let _mock = MockLvm::with_standard_fixtures();
+    assert_eq!(Lvm::run_json_pointer("vgs", pointer), expected);
+}
+
+#[test]
+fn segment_info() {
+    let output = r#"{"report":[{"seg":[{"seg_start":"0 ","seg_size":"4.00g","segtype":"linear","devices":"/dev/sdb(0)"}]}]}"#;
+    let _mock = MockLvm::new().respond(
+        "lvs -a --segments -o seg_start,seg_size,segtype,devices 'vg0/data'",
+        Ok(output.to_string()),
+    );
+    let segments = Lvm::segment_info("vg0", "data").unwrap();
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].devices[0].device, "/dev/sdb");
+}
+
+#[test]
+fn pvsegs() {
+    let output = r#"{"report":[{"pvseg":[{"pvseg_start":"0","pvseg_size":"1024","lv_name":"data","segtype":"linear"},{"pvseg_start":"1024","pvseg_size":"1535","lv_name":"","segtype":"free"}]}]}"#;
+    let _mock = MockLvm::new().respond(
+        "pvs --segments -o pvseg_start,pvseg_size,lv_name,segtype '/dev/sdb'",
+        Ok(output.to_string()),
+    );
+    let segments = Lvm::pvsegs(Path::new("/dev/sdb")).unwrap();
+    assert_eq!(segments[0].lv_name.as_deref(), Some("data"));
+    assert_eq!(segments[1].lv_name, None);
+    assert_eq!(
+        Lvm::pvsegs(Path::new("/dev/sd b")),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
+
+#[rstest]
+#[case(Ok(String::new()), Ok(true))]
+#[case(Err(CommandRetCode::ProcessingFailed), Ok(false))]
+#[case(
+    Err(CommandRetCode::InvalidParameters),
+    Err(CommandRetCode::InvalidParameters)
+)]
+fn check_vg_consistency(
+    #[case] response: Result<String, CommandRetCode>,
+    #[case] expected: Result<bool, CommandRetCode>,
+) {
+    let _mock = MockLvm::new().respond("vgck 'vg0'", response);
+    assert_eq!(
+        Lvm::check_vg_consistency("vg0").map(|report| report.is_consistent),
+        expected
+    );
+}
+
+#[rstest]
+#[case("-wi-a-----", false, Ok(()))]
+#[case("-wi-ao----", false, Err(CommandRetCode::DataLossRisk))]
+#[case("-wi-ao----", true, Ok(()))]
+fn lvreduce(#[case] attr: &str, #[case] force: bool, #[case] expected: Result<(), CommandRetCode>) {
+    let _mock = MockLvm::new()
+        .respond(
+            "lvs 'vg0/data'",
+            Ok(LVS_FIXTURE.replace("-wi-a-----", attr)),
+        )
+        .respond(
+            "lvreduce --yes -L 1073741824b 'vg0/data'",
+            Ok(String::new()),
+        )
+        .respond(
+            "lvreduce --yes --force -L 1073741824b 'vg0/data'",
+            Ok(String::new()),
+        );
+    assert_eq!(
+        Lvm::lvreduce("vg0", "data", ByteSize::from_gib(1), force),
+        expected
+    );
+}
+
+#[rstest]
+#[case(Ok(VGS_FIXTURE.to_string()), 1)]
+#[case(Err(CommandRetCode::ProcessingFailed), 5)]
+#[case(Err(CommandRetCode::Unknown(42)), 42)]
+fn run_with_retcode(#[case] response: Result<String, CommandRetCode>, #[case] expected: i32) {
+    let _mock = MockLvm::new().respond("vgs", response.clone());
+    let (res, code) = Lvm::run_with_retcode("vgs");
+    assert_eq!(res.is_ok(), response.is_ok());
+    assert_eq!(code, expected);
+}
+
+#[rstest]
+#[case(VerbosityLevel::Normal, "vgs")]
+#[case(VerbosityLevel::Verbose, "vgs -v")]
+#[case(VerbosityLevel::VeryVerbose, "vgs -vv")]
+#[case(VerbosityLevel::Debug, "vgs -vvv")]
+fn run_with_verbosity(#[case] level: VerbosityLevel, #[case] command: &str) {
+    let _mock = MockLvm::new().respond(command, Ok(VGS_FIXTURE.to_string()));
+    let (report, logs) = Lvm::run_with_verbosity("vgs", level).unwrap();
+    assert!(report.contains_key("report"));
+    assert!(logs.is_empty());
+}
+
+#[test]
+fn pvs_with_missing() {
+    let output = r#"PARTIAL MODE. Incomplete logical volumes will be processed.{"report":[{"pv":[
+        {"pv_name":"/dev/sdb","vg_name":"vg0","pv_fmt":"lvm2","pv_attr":"a--","pv_size":"<10.00g","pv_free":"<6.00g"},
+        {"pv_name":"[unknown]","vg_name":"vg0","pv_fmt":"lvm2","pv_attr":"a-m","pv_size":"<10.00g","pv_free":"<10.00g"}
+    ]}]}"#;
+    let _mock = MockLvm::new().respond("pvs --partial", Ok(output.to_string()));
+    let missing: Vec<_> = Lvm::pvs_with_missing()
+        .unwrap()
+        .into_iter()
+        .filter(|pv| pv.is_missing())
+        .map(|pv| pv.pv_name)
+        .collect();
+    assert_eq!(missing, ["[unknown]"]);
+}
+
+#[rstest]
+#[case("2.03.16(2) (2022-05-18)", true)]
+#[case("2.02.174(2) (2017-09-13)", false)]
+fn lvs_historical(#[case] version: &str, #[case] supported: bool) {
+    let command = "lvs -H -o lv_name,vg_name,lv_time_removed,lv_uuid,lv_historical 'vg0'";
+    let output = r#"{"report":[{"lv":[
+        {"lv_name":"thin1","vg_name":"vg0","lv_time_removed":"","lv_uuid":"uuid-1","lv_historical":""},
+        {"lv_name":"-thin0","vg_name":"vg0","lv_time_removed":"2024-01-31 12:00:00 +0000","lv_uuid":"uuid-0","lv_historical":"historical"}
+    ]}]}"#;
+    let _mock = MockLvm::new()
+        .respond("version", Ok(format!("  LVM version:     {version}")))
+        .respond(command, Ok(output.to_string()));
+    let expected = HistoricalLvInfo {
+        lv_name: "thin0".to_string(),
+        vg_name: "vg0".to_string(),
+        lv_time_removed: "2024-01-31 12:00:00 +0000".to_string(),
+        lv_uuid: "uuid-0".to_string(),
+    };
+    if supported {
+        assert_eq!(Lvm::lvs_historical("vg0").unwrap(), [expected]);
+    } else {
+        assert_eq!(
+            Lvm::lvs_historical("vg0"),
+            Err(CommandRetCode::FeatureNotSupported)
+        );
+    }
+}
+
+#[test]
+fn run_idempotent() {
+    let _mock = MockLvm::with_standard_fixtures().respond(
+        "vgcreate 'vg1' /dev/sdc",
+        Err(CommandRetCode::ProcessingFailed),
+    );
+    assert!(Lvm::run_idempotent("vgs").unwrap().contains_key("report"));
+    // failures without the message stay failures
+    assert_eq!(
+        Lvm::run_idempotent("vgcreate 'vg1' /dev/sdc"),
+        Err(CommandRetCode::ProcessingFailed)
+    );
+}
+
+#[test]
+fn lvs_orphaned() {
+    let output = r#"{"report":[{"lv":[
+        {"lv_name":"pool", "vg_name":"vg0", "lv_attr":"twi-aotz--", "lv_size":"10.00g", "pool_lv":"", "origin":"", "data_percent":"2.15", "metadata_percent":"11.08", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""},
+        {"lv_name":"old_snap", "vg_name":"vg0", "lv_attr":"Vwi---tz-k", "lv_size":"5.00g", "pool_lv":"pool", "origin":"", "data_percent":"", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""},
+        {"lv_name":"data", "vg_name":"vg0", "lv_attr":"owi-aos---", "lv_size":"4.00g", "pool_lv":"", "origin":"", "data_percent":"", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""},
+        {"lv_name":"snap", "vg_name":"vg0", "lv_attr":"swi-I-s---", "lv_size":"1.00g", "pool_lv":"", "origin":"data", "data_percent":"100.00", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""}
+    ]}]}"#;
+    let _mock = MockLvm::new().respond(r#"lvs --select 'lv_parent=""'"#, Ok(output.to_string()));
+    let orphaned: Vec<_> = Lvm::lvs_orphaned()
+        .unwrap()
+        .into_iter()
+        .map(|orphan| (orphan.lv.lv_name, orphan.orphan_reason))
+        .collect();
+    assert_eq!(
+        orphaned,
+        [
+            ("old_snap".to_string(), OrphanReason::MissingOrigin),
+            ("snap".to_string(), OrphanReason::DetachedSnapshot)
+        ]
+    );
+}
+
+#[test]
+fn pv_drain() {
+    let dev = Path::new("/dev/null");
+    let pvs = PVS_FIXTURE.replace("a--", "---");
+    let _mock = MockLvm::new()
+        .respond("pvs /dev/null", Ok(pvs))
+        .respond("pvchange --allocatable n /dev/null", Ok(String::new()))
+        .respond("pvmove /dev/null", Ok(String::new()));
+    assert!(!Lvm::pv_is_allocatable(dev).unwrap());
+    Lvm::pv_drain(dev).unwrap();
+}
+
+#[test]
+fn pv_allocatable() {
+    let _mock = MockLvm::new()
+        .respond("pvs /dev/null", Ok(PVS_FIXTURE.to_string()))
+        .respond("pvchange --allocatable y /dev/null", Ok(String::new()));
+    Lvm::pv_allocatable(Path::new("/dev/null"), true).unwrap();
+}
+
+#[test]
+fn pv_allocatable_of_unknown_pv() {
+    let _mock = MockLvm::new().respond("pvs /dev/null", Err(CommandRetCode::ProcessingFailed));
+    assert_eq!(
+        Lvm::pv_allocatable(Path::new("/dev/null"), true),
+        Err(CommandRetCode::ProcessingFailed)
+    );
+}
+
+#[rstest]
+#[case(Ok(LVS_FIXTURE.replace("-wi-a-----", "-wi-------")), Ok(()))]
+#[case(Ok(LVS_FIXTURE.to_string()), Err(CommandRetCode::VgHasActiveLvs))]
+#[case(Err(CommandRetCode::InitFailed), Err(CommandRetCode::InitFailed))]
+#[case(
+    Err(CommandRetCode::ProcessingFailed),
+    Err(CommandRetCode::ProcessingFailed)
+)]
+fn vg_import(
+    #[case] lvs: Result<String, CommandRetCode>,
+    #[case] expected: Result<(), CommandRetCode>,
+) {
+    let _mock = MockLvm::new()
+        .respond("lvs --devices '/dev/null' 'vg0'", lvs)
+        .respond("vgimport --devices '/dev/null' 'vg0'", Ok(String::new()));
+    assert_eq!(Lvm::vg_import("vg0", &[Path::new("/dev/null")]), expected);
+}
+
+#[test]
+fn thin_commands() {
+    let _mock = MockLvm::new()
+        .respond(
+            "lvcreate --type thin-pool -L 1073741824b -c 65536b -n 'pool' 'vg0'",
+            Ok(String::new()),
+        )
+        .respond(
+            "lvcreate --type thin -V 2147483648b --thinpool 'pool' -n 'thin' 'vg0'",
+            Ok(String::new()),
+        );
+    Lvm::thin()
+        .create_pool("vg0", "pool", ByteSize(1 << 30), Some(ByteSize(64 << 10)))
+        .unwrap();
+    Lvm::thin()
+        .create_thin_lv("vg0", "pool", "thin", ByteSize(2 << 30))
+        .unwrap();
+}
+
+#[test]
+fn cache_commands() {
+    let _mock = MockLvm::new()
+        .respond(
+            "lvcreate --type cache-pool -L 1073741824b --poolmetadatasize 8388608b --cachepolicy smq -n 'cpool' 'vg0'",
+            Ok(String::new()),
+        )
+        .respond(
+            "lvcreate --type cache-pool -L 1073741824b --cachepolicy mq -n 'cpool' 'vg0'",
+            Ok(String::new()),
+        )
+        .respond(
+            "lvconvert --yes --type cache --cachepool 'vg0/cpool' 'vg0/data'",
+            Ok(String::new()),
+        )
+        .respond("lvconvert --yes --uncache 'vg0/data'", Ok(String::new()));
+    Lvm::create_cache_pool(
+        "vg0",
+        "cpool",
+        ByteSize::from_gib(1),
+        Some(ByteSize::from_mib(8)),
+        CachePolicy::Smq,
+    )
+    .unwrap();
+    Lvm::create_cache_pool("vg0", "cpool", ByteSize::from_gib(1), None, CachePolicy::Mq).unwrap();
+    Lvm::attach_cache("vg0", "data", "cpool").unwrap();
+    Lvm::detach_cache("vg0", "data").unwrap();
+}
+
+#[rstest]
+#[case(LockMode::Shared, "vgchange --lock-opt sh 'vg0'")]
+#[case(LockMode::Exclusive, "vgchange --lock-opt ex 'vg0'")]
+fn vg_lock(#[case] mode: LockMode, #[case] command: &str) {
+    let _mock = MockLvm::new()
+        .respond(command, Ok(String::new()))
+        .respond("vgchange --lock-opt none 'vg0'", Ok(String::new()));
+    let _guard = Lvm::vg_lock("vg0", mode).unwrap();
+    Lvm::vg_unlock("vg0").unwrap();
+}
+
+#[test]
+fn run_on_devices() {
+    let _mock = MockLvm::new().respond(
+        "pvs --devices '/dev/sdb,/dev/mapper/mpatha'",
+        Ok(PVS_FIXTURE.to_string()),
+    );
+    let devices = [Path::new("/dev/sdb"), Path::new("/dev/mapper/mpatha")];
+    assert!(
+        Lvm::run_on_devices("pvs", &devices)
+            .unwrap()
+            .contains_key("report")
+    );
+}
+
+#[rstest]
+#[case("test.devices")]
+#[case("/etc/lvm/devices/test.devices")]
+fn run_with_devicesfile(#[case] path: &str) {
+    let _mock = MockLvm::new().respond(
+        "pvs --devicesfile 'test.devices'",
+        Ok(PVS_FIXTURE.to_string()),
+    );
+    assert!(
+        Lvm::run_with_devicesfile("pvs", Path::new(path))
+            .unwrap()
+            .contains_key("report")
+    );
+}
+
+#[rstest]
+#[case(true, &["a"], "vgchange --addtag 'a' 'vg0'")]
+#[case(false, &["a", "b_+.-1"], "vgchange --deltag 'a' --deltag 'b_+.-1' 'vg0'")]
+fn vgchange_tags(#[case] add: bool, #[case] tags: &[&str], #[case] command: &str) {
+    let _mock = MockLvm::new().respond(command, Ok(String::new()));
+    if add {
+        Lvm::vg_tags_add("vg0", tags).unwrap();
+    } else {
+        Lvm::vg_tags_remove("vg0", tags).unwrap();
+    }
+}
+
+#[rstest]
+#[case(true, &["a", "b"], "lvchange --addtag 'a' --addtag 'b' 'vg0/data'")]
+#[case(false, &["a"], "lvchange --deltag 'a' 'vg0/data'")]
+fn lvchange_tags(#[case] add: bool, #[case] tags: &[&str], #[case] command: &str) {
+    let _mock = MockLvm::new().respond(command, Ok(String::new()));
+    if add {
+        Lvm::lv_tags_add("vg0", "data", tags).unwrap();
+    } else {
+        Lvm::lv_tags_remove("vg0", "data", tags).unwrap();
+    }
+}
+
+#[test]
+fn vg_manager_quotes_names() {
+    let dev = Path::new("/dev/null");
+    let _mock = MockLvm::new()
+        .respond("vgcreate 'vg1' /dev/null", Ok(String::new()))
+        .respond("vgextend 'vg1' /dev/null", Ok(String::new()))
+        .respond("vgreduce 'vg1' /dev/null", Ok(String::new()))
+        .respond("vgrename 'vg1' 'vg2'", Ok(String::new()))
+        .respond("vgremove 'vg2'", Ok(String::new()));
+    Lvm::vg().vgcreate("vg1", &[dev]).unwrap();
+    Lvm::vg().vgextend("vg1", &[dev]).unwrap();
+    Lvm::vg().vgreduce("vg1", &[dev]).unwrap();
+    Lvm::vg().vgrename("vg1", "vg2").unwrap();
+    Lvm::vg().vgremove("vg2").unwrap();
+}
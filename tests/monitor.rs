@@ -0,0 +1,24 @@
+use lvm_sys2::{
+    lvm::{CommandRetCode, Lvm},
+    monitor::DmEvent,
+};
+use rstest::rstest;
+
+#[rstest]
+#[case("vg0", "pool", &[])]
+#[case("vg0", "a/b", &[DmEvent::ThinPoolSpaceUsage])]
+#[case("vg 0", "pool", &[DmEvent::RaidDegradation])]
+fn register_rejects_invalid(#[case] vg: &str, #[case] lv: &str, #[case] events: &[DmEvent]) {
+    assert_eq!(
+        Lvm::register_event(vg, lv, events).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[test]
+fn unregister_rejects_invalid() {
+    assert_eq!(
+        Lvm::unregister_event("vg0", "").unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
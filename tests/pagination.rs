@@ -0,0 +1,9 @@
+use lvm_sys2::lvm::{CommandRetCode, Lvm};
+
+#[test]
+fn zero_page_size_is_rejected() {
+    assert_eq!(
+        Lvm::run_paginated("lvs", 0, 0),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
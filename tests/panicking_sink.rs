@@ -0,0 +1,18 @@
+// own test binary: the log sink is process-wide
+use std::sync::Arc;
+
+use lvm_sys2::lvm::{LogLevel, Lvm, LvmLogSink};
+
+struct PanickingSink;
+
+impl LvmLogSink for PanickingSink {
+    fn log(&self, _level: LogLevel, _file: &str, _line: i32, _message: &str) {
+        panic!("sink failure");
+    }
+}
+
+#[test]
+fn panicking_sink_does_not_abort() {
+    Lvm::set_log_sink(Arc::new(PanickingSink));
+    assert!(Lvm::run("vgs").is_ok());
+}
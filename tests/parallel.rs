@@ -0,0 +1,24 @@
+#![cfg(feature = "unsafe-parallel")]
+
+use std::thread;
+
+use lvm_sys2::lvm::{CommandRetCode, Lvm};
+
+#[test]
+fn thread_local_handles() {
+    let threads: Vec<_> = (0..2)
+        // SAFETY: read-only queries, nothing else in this test binary uses the global singleton
+        .map(|_| thread::spawn(|| unsafe { Lvm::with_thread_local(|lvm| lvm.run_local("vgs")) }))
+        .collect();
+    for t in threads {
+        assert!(t.join().unwrap().unwrap().unwrap().contains_key("report"));
+    }
+}
+
+#[test]
+fn nested_thread_local() {
+    // SAFETY: no commands are run at all
+    let nested =
+        unsafe { Lvm::with_thread_local(|_lvm| Lvm::with_thread_local(|_lvm| ())) }.unwrap();
+    assert_eq!(nested, Err(CommandRetCode::AlreadyInitialized));
+}
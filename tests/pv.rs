@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use lvm_sys2::{
+    lvm::{CommandRetCode, Lvm},
+    units::ByteSize,
+};
+
+#[test]
+fn missing_device_is_rejected() {
+    let missing = Path::new("/dev/lvm-sys2-missing-device");
+    assert!(matches!(
+        Lvm::pv().create(missing),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+    assert!(matches!(
+        Lvm::pv().remove(missing),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+    assert!(matches!(
+        Lvm::pv().move_extents(missing, None),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+    assert!(matches!(
+        Lvm::wipesignatures(missing, true),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+    assert_eq!(
+        Lvm::pv_drain(missing),
+        Err(CommandRetCode::InvalidParameters)
+    );
+    assert_eq!(
+        Lvm::pv_is_allocatable(missing),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
+
+#[test]
+fn zero_resize_is_rejected() {
+    assert!(matches!(
+        Lvm::pv().resize(Path::new("/dev/null"), Some(ByteSize(0))),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+}
+
+#[test]
+fn pvmove_async_rejects_missing_device() {
+    let missing = Path::new("/dev/lvm-sys2-missing-device");
+    assert!(matches!(
+        Lvm::pvmove_async(missing, None, |_progress| ()),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+    assert!(matches!(
+        Lvm::pvmove_async(Path::new("/dev/null"), Some(missing), |_progress| ()),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+}
@@ -0,0 +1,239 @@
+use lvm_sys2::{
+    lvm::CommandRetCode,
+    report::{
+        DiskPath, FullReport, LvDetail, LvInfo, LvReport, PvDetail, PvInfo, PvReport, PvSegInfo,
+        Report, SegmentDevice, SegmentInfo, VgDetail, VgReport,
+    },
+    units::ByteSize,
+};
+use rstest::rstest;
+use std::path::Path;
+
+#[test]
+fn deserialize_pvs() {
+    let json = r#"{"report": [{"pv": [
+        {"pv_name":"/dev/sda2", "vg_name":"system", "pv_fmt":"lvm2", "pv_attr":"a--", "pv_size":"<10.00g", "pv_free":"0 "},
+        {"pv_name":"/dev/sdb", "vg_name":"", "pv_fmt":"lvm2", "pv_attr":"---", "pv_size":"1.00g", "pv_free":"1.00g"}
+    ]}]}"#;
+    let report: Report<PvReport> = serde_json::from_str(json).unwrap();
+    let pvs = &report.report[0].pv;
+    assert_eq!(pvs[0].vg_name.as_deref(), Some("system"));
+    assert_eq!(pvs[0].pv_free, ByteSize(0));
+    assert_eq!(pvs[1].vg_name, None);
+    assert_eq!(pvs[1].pv_uuid, None);
+}
+
+#[test]
+fn deserialize_vgs() {
+    let json = r#"{"report": [{"vg": [
+        {"vg_name":"system", "pv_count":"1", "lv_count":"2", "snap_count":"0", "vg_attr":"wz--n-", "vg_size":"<10.00g", "vg_free":"0 "}
+    ]}]}"#;
+    let report: Report<VgReport> = serde_json::from_str(json).unwrap();
+    let vg = &report.report[0].vg[0];
+    assert_eq!(vg.lv_count, 2);
+    assert_eq!(vg.vg_extent_size, None);
+}
+
+#[test]
+fn deserialize_lvs() {
+    let json = r#"{"report": [{"lv": [
+        {"lv_name":"root", "vg_name":"system", "lv_attr":"-wi-ao----", "lv_size":"8.00g", "pool_lv":"", "origin":"", "data_percent":"", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""},
+        {"lv_name":"thin", "vg_name":"system", "lv_attr":"Vwi-a-tz--", "lv_size":"1.00g", "pool_lv":"pool", "origin":"", "data_percent":"12.50", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""}
+    ]}]}"#;
+    let report: Report<LvReport> = serde_json::from_str(json).unwrap();
+    let lvs = &report.report[0].lv;
+    assert_eq!(lvs[0].pool_lv, None);
+    assert_eq!(lvs[1].pool_lv.as_deref(), Some("pool"));
+    assert_eq!(lvs[1].data_percent, Some(12.5));
+}
+
+#[test]
+fn deserialize_fullreport() {
+    let json = r#"{"report": [
+        {
+            "vg": [{"vg_name":"system", "vg_uuid":"abc", "pv_count":"1", "lv_count":"1", "snap_count":"0", "vg_attr":"wz--n-", "vg_size":"<10.00g", "vg_free":"0 "}],
+            "pv": [{"pv_name":"/dev/sda2", "pv_fmt":"lvm2", "pv_attr":"a--", "pv_size":"<10.00g", "pv_free":"0 ", "pv_uuid":"def"}],
+            "lv": [{"lv_name":"root", "lv_attr":"-wi-ao----", "lv_size":"<10.00g", "lv_uuid":"ghi"}],
+            "pvseg": [],
+            "seg": []
+        },
+        {
+            "vg": [],
+            "pv": [{"pv_name":"/dev/sdb", "pv_fmt":"lvm2", "pv_attr":"---", "pv_size":"1.00g", "pv_free":"1.00g"}],
+            "lv": []
+        }
+    ]}"#;
+    let full: FullReport = serde_json::from_str(json).unwrap();
+    assert_eq!(full.vg.len(), 1);
+    assert_eq!(full.pv.len(), 2);
+    assert_eq!(full.pv[0].vg_name.as_deref(), Some("system"));
+    assert_eq!(full.pv[1].vg_name, None);
+    assert_eq!(full.lv[0].vg_name, "system");
+}
+
+#[test]
+fn deserialize_pvdisplay() {
+    let json = r#"{"pv_name":"/dev/sdb", "vg_name":"", "pv_uuid":"abc", "pv_fmt":"lvm2", "pv_attr":"---", "pv_size":"1.00g", "pv_free":"1.00g", "pv_used":"0 ", "dev_size":"1.00g", "pe_start":"1.00m", "pv_pe_count":"0", "pv_pe_alloc_count":"0", "pv_mda_count":"1", "pv_mda_used_count":"1", "pv_mda_size":"1020.00k", "pv_mda_free":"1020.00k"}"#;
+    let pv: PvDetail = serde_json::from_str(json).unwrap();
+    assert_eq!(pv.vg_name, None);
+    assert_eq!(pv.pe_start, ByteSize::from_mib(1));
+    assert_eq!(pv.pv_mda_count, 1);
+}
+
+#[test]
+fn deserialize_vgdisplay() {
+    let json = r#"{"vg_name":"system", "vg_uuid":"abc", "vg_fmt":"lvm2", "vg_attr":"wz--n-", "vg_size":"<10.00g", "vg_free":"0 ", "vg_extent_size":"4.00m", "vg_extent_count":"2559", "vg_free_count":"0", "max_lv":"0", "max_pv":"0", "pv_count":"1", "lv_count":"2", "snap_count":"0", "vg_seqno":"3", "vg_mda_count":"1", "vg_mda_used_count":"1", "vg_mda_copies":"unmanaged"}"#;
+    let vg: VgDetail = serde_json::from_str(json).unwrap();
+    assert_eq!(vg.vg_extent_count, 2559);
+    assert_eq!(vg.vg_seqno, 3);
+}
+
+#[test]
+fn deserialize_lvdisplay() {
+    let json = r#"{"lv_name":"root", "vg_name":"system", "lv_uuid":"abc", "lv_path":"/dev/system/root", "lv_attr":"-wi-a-----", "lv_size":"8.00g", "lv_layout":"linear", "lv_role":"public", "lv_active":"active", "seg_count":"1", "lv_kernel_major":"-1", "lv_kernel_minor":"-1", "lv_time":"2024-01-01 12:00:00 +0000", "lv_host":"host", "pool_lv":"", "origin":"", "data_percent":""}"#;
+    let lv: LvDetail = serde_json::from_str(json).unwrap();
+    assert_eq!(lv.lv_kernel_major, -1);
+    assert_eq!(lv.pool_lv, None);
+}
+
+#[test]
+fn deserialize_segments() {
+    let json = r#"[
+        {"seg_start":"0 ", "seg_size":"4.00g", "segtype":"striped", "devices":"/dev/sdb(0),/dev/sdc(10)"},
+        {"seg_start":"4.00g", "seg_size":"1.00g", "segtype":"linear", "devices":"/dev/sdb(1024)"},
+        {"seg_start":"0 ", "seg_size":"1.00g", "segtype":"thin", "devices":""}
+    ]"#;
+    let segments: Vec<SegmentInfo> = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        segments[0].devices,
+        [
+            SegmentDevice {
+                device: "/dev/sdb".to_string(),
+                start_extent: 0
+            },
+            SegmentDevice {
+                device: "/dev/sdc".to_string(),
+                start_extent: 10
+            }
+        ]
+    );
+    assert_eq!(segments[1].seg_start, ByteSize::from_gib(4));
+    assert_eq!(segments[1].devices[0].start_extent, 1024);
+    assert_eq!(segments[2].seg_type, "thin");
+    assert!(segments[2].devices.is_empty());
+}
+
+#[test]
+fn deserialize_pvsegs() {
+    let json = r#"[
+        {"pvseg_start":"0", "pvseg_size":"1024", "lv_name":"root", "segtype":"linear"},
+        {"pvseg_start":"1024", "pvseg_size":"1535", "lv_name":"", "segtype":"free"}
+    ]"#;
+    let segments: Vec<PvSegInfo> = serde_json::from_str(json).unwrap();
+    assert_eq!(segments[0].lv_name.as_deref(), Some("root"));
+    assert_eq!(
+        segments[1],
+        PvSegInfo {
+            pvseg_start: 1024,
+            pvseg_size: 1535,
+            lv_name: None,
+            seg_type: "free".to_string(),
+        }
+    );
+}
+
+#[rstest]
+#[case("/dev/sdb")]
+#[case("/dev/sdb(x)")]
+#[case("/dev/sdb(0")]
+fn invalid_segment_devices(#[case] devices: &str) {
+    let json = format!(
+        r#"{{"seg_start":"0 ", "seg_size":"4.00g", "segtype":"linear", "devices":"{devices}"}}"#
+    );
+    assert!(serde_json::from_str::<SegmentInfo>(&json).is_err());
+}
+
+#[rstest]
+#[case("-wi-ao----", true)]
+#[case("-wi-a-----", true)]
+#[case("-wi-------", false)]
+#[case("Vwi---tz-k", false)]
+#[case("", false)]
+fn lv_is_active(#[case] attr: &str, #[case] active: bool) {
+    let json =
+        format!(r#"{{"lv_name":"lv", "vg_name":"vg", "lv_attr":"{attr}", "lv_size":"1.00g"}}"#);
+    let lv: LvInfo = serde_json::from_str(&json).unwrap();
+    assert_eq!(lv.is_active(), active);
+}
+
+#[rstest]
+#[case("a--", false)]
+#[case("a-m", true)]
+#[case("--m", true)]
+#[case("", false)]
+fn pv_is_missing(#[case] attr: &str, #[case] missing: bool) {
+    let json = format!(
+        r#"{{"pv_name":"[unknown]", "vg_name":"vg", "pv_fmt":"lvm2", "pv_attr":"{attr}", "pv_size":"1.00g", "pv_free":"0 "}}"#
+    );
+    let pv: PvInfo = serde_json::from_str(&json).unwrap();
+    assert_eq!(pv.is_missing(), missing);
+}
+
+#[rstest]
+#[case("a--", true)]
+#[case("---", false)]
+#[case("", false)]
+fn pv_is_allocatable(#[case] attr: &str, #[case] allocatable: bool) {
+    let json = format!(
+        r#"{{"pv_name":"/dev/sdb", "vg_name":"vg", "pv_fmt":"lvm2", "pv_attr":"{attr}", "pv_size":"1.00g", "pv_free":"0 "}}"#
+    );
+    let pv: PvInfo = serde_json::from_str(&json).unwrap();
+    assert_eq!(pv.is_allocatable(), allocatable);
+}
+
+#[rstest]
+#[case("-wi-ao----", true)]
+#[case("-wi-a-----", false)]
+#[case("-wi---", false)]
+fn lv_is_open(#[case] attr: &str, #[case] open: bool) {
+    let json =
+        format!(r#"{{"lv_name":"lv", "vg_name":"vg", "lv_attr":"{attr}", "lv_size":"1.00g"}}"#);
+    let lv: LvInfo = serde_json::from_str(&json).unwrap();
+    assert_eq!(lv.is_open(), open);
+}
+
+#[rstest]
+#[case("twi-a-tz--", true)]
+#[case("twi-a-t---", false)]
+#[case("-wi-a-----", false)]
+#[case("twi", false)]
+fn lv_is_zeroed(#[case] attr: &str, #[case] zeroed: bool) {
+    let json =
+        format!(r#"{{"lv_name":"lv", "vg_name":"vg", "lv_attr":"{attr}", "lv_size":"1.00g"}}"#);
+    let lv: LvInfo = serde_json::from_str(&json).unwrap();
+    assert_eq!(lv.is_zeroed(), zeroed);
+}
+
+#[test]
+fn disk_path_of_lv() {
+    let json = r#"{"lv_name":"data", "vg_name":"vg0", "lv_attr":"-wi-a-----", "lv_size":"1.00g"}"#;
+    let lv: LvInfo = serde_json::from_str(json).unwrap();
+    let path = DiskPath::from(&lv);
+    assert_eq!(path.as_ref(), Path::new("/dev/vg0/data"));
+    assert_eq!(path, DiskPath::try_from("/dev/vg0/data").unwrap());
+}
+
+#[rstest]
+#[case("")]
+#[case("/dev/sdb")]
+#[case("/dev/vg0/")]
+#[case("/dev/vg0/data/x")]
+#[case("/dev/vg 0/data")]
+#[case("/mnt/vg0/data")]
+#[case("/dev/vg0/snapshot0")]
+fn invalid_disk_path(#[case] path: &str) {
+    assert_eq!(
+        DiskPath::try_from(path),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use lvm_sys2::{
+    lvm::{CommandRetCode, Lvm},
+    retry::{RecoverableError, RetryPolicy},
+};
+use rstest::rstest;
+
+#[rstest]
+#[case(CommandRetCode::ProcessingFailed, true)]
+#[case(CommandRetCode::Timeout, true)]
+#[case(CommandRetCode::Unknown(42), true)]
+#[case(CommandRetCode::InvalidParameters, false)]
+#[case(
+    CommandRetCode::RetriesExhausted(Box::new(CommandRetCode::Timeout)),
+    false
+)]
+fn into_recoverable(#[case] code: CommandRetCode, #[case] recoverable: bool) {
+    match code.clone().into_recoverable() {
+        Some(e) => {
+            assert!(recoverable);
+            assert_eq!(e.code(), &code);
+            assert_eq!(CommandRetCode::from(e), code);
+        }
+        None => assert!(!recoverable),
+    }
+}
+
+#[test]
+fn try_from_returns_permanent_code() {
+    assert_eq!(
+        RecoverableError::try_from(CommandRetCode::InitFailed),
+        Err(CommandRetCode::InitFailed)
+    );
+    assert_eq!(
+        RecoverableError::try_from(CommandRetCode::Timeout).map(RecoverableError::into_inner),
+        Ok(CommandRetCode::Timeout)
+    );
+}
+
+#[rstest]
+#[case(-1.0)]
+#[case(f64::NAN)]
+#[case(f64::INFINITY)]
+fn invalid_backoff_is_rejected(#[case] backoff_factor: f64) {
+    let policy = RetryPolicy {
+        max_attempts: 3,
+        initial_delay: Duration::from_millis(10),
+        backoff_factor,
+    };
+    assert_eq!(
+        Lvm::run_with_retry("vgs", policy),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "serde")]
+
+use std::ffi::CString;
+
+use lvm_sys2::{error::LvmError, lvm::CommandRetCode};
+use rstest::rstest;
+use serde_json::{Value, json};
+
+#[rstest]
+#[case(CommandRetCode::Timeout, json!({"kind": "Timeout"}))]
+#[case(CommandRetCode::Unknown(42), json!({"kind": "Unknown", "code": 42}))]
+#[case(
+    CommandRetCode::RetriesExhausted(Box::new(CommandRetCode::ProcessingFailed)),
+    json!({"kind": "RetriesExhausted", "last": {"kind": "ProcessingFailed"}})
+)]
+#[case(
+    CommandRetCode::UnexpectedOutput("garbage".to_string()),
+    json!({"kind": "UnexpectedOutput", "output": "garbage"})
+)]
+#[case(
+    CommandRetCode::JsonPointerNotFound("/report/1".to_string()),
+    json!({"kind": "JsonPointerNotFound", "pointer": "/report/1"})
+)]
+fn command_ret_code(#[case] code: CommandRetCode, #[case] expected: Value) {
+    assert_eq!(serde_json::to_value(code).unwrap(), expected);
+}
+
+#[test]
+fn wrapped_errors_are_strings() {
+    let nul = CString::new("pvs\0").unwrap_err();
+    let message = nul.to_string();
+    assert_eq!(
+        serde_json::to_value(CommandRetCode::InvalidCommandLine(nul)).unwrap(),
+        json!({"kind": "InvalidCommandLine", "message": message})
+    );
+    let e = serde_json::from_str::<Value>("{").unwrap_err();
+    let cause = e.to_string();
+    assert_eq!(
+        serde_json::to_value(CommandRetCode::JsonDeserializationFailed((
+            e,
+            "{".to_string()
+        )))
+        .unwrap(),
+        json!({"kind": "JsonDeserializationFailed", "cause": cause, "raw": "{"})
+    );
+}
+
+#[rstest]
+#[case(
+    LvmError::VgNotFound("Volume group \"vg0\" not found".to_string()),
+    json!({"kind": "VgNotFound", "message": "Volume group \"vg0\" not found"})
+)]
+#[case(
+    LvmError::Other { code: CommandRetCode::InitFailed, messages: vec!["oops".to_string()] },
+    json!({"kind": "Other", "code": {"kind": "InitFailed"}, "messages": ["oops"]})
+)]
+fn lvm_error(#[case] error: LvmError, #[case] expected: Value) {
+    assert_eq!(serde_json::to_value(error).unwrap(), expected);
+}
@@ -0,0 +1,14 @@
+#![cfg(feature = "log")]
+
+use lvm_sys2::lvm::LogLevel;
+use rstest::rstest;
+
+#[rstest]
+#[case(LogLevel::FATAL, log::Level::Error)]
+#[case(LogLevel::PRINT, log::Level::Info)]
+#[case(LogLevel::VERBOSE, log::Level::Debug)]
+#[case(LogLevel::VERY_VERBOSE, log::Level::Trace)]
+#[case(LogLevel::DEBUG, log::Level::Trace)]
+fn log_level_mapping(#[case] level: LogLevel, #[case] expected: log::Level) {
+    assert_eq!(log::Level::from(level), expected);
+}
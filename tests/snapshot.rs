@@ -0,0 +1,45 @@
+use lvm_sys2::{report::LvInfo, snapshot::OrphanReason};
+use rstest::rstest;
+
+// rows of `lvs --reportformat json` of a VG with a thin pool and COW snapshots
+#[rstest]
+#[case(
+    r#"{"lv_name":"pool", "vg_name":"vg0", "lv_attr":"twi-aotz--", "lv_size":"10.00g", "pool_lv":"", "origin":"", "data_percent":"2.15", "metadata_percent":"11.08", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""}"#,
+    None
+)]
+#[case(
+    r#"{"lv_name":"thin", "vg_name":"vg0", "lv_attr":"Vwi-a-tz--", "lv_size":"5.00g", "pool_lv":"pool", "origin":"", "data_percent":"4.30", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""}"#,
+    None
+)]
+#[case(
+    r#"{"lv_name":"thin_snap", "vg_name":"vg0", "lv_attr":"Vwi---tz-k", "lv_size":"5.00g", "pool_lv":"pool", "origin":"thin", "data_percent":"", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""}"#,
+    None
+)]
+#[case(
+    r#"{"lv_name":"old_snap", "vg_name":"vg0", "lv_attr":"Vwi---tz-k", "lv_size":"5.00g", "pool_lv":"pool", "origin":"", "data_percent":"", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""}"#,
+    Some(OrphanReason::MissingOrigin)
+)]
+#[case(
+    r#"{"lv_name":"data", "vg_name":"vg0", "lv_attr":"owi-aos---", "lv_size":"4.00g", "pool_lv":"", "origin":"", "data_percent":"", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""}"#,
+    None
+)]
+#[case(
+    r#"{"lv_name":"snap", "vg_name":"vg0", "lv_attr":"swi-a-s---", "lv_size":"1.00g", "pool_lv":"", "origin":"data", "data_percent":"12.37", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""}"#,
+    None
+)]
+#[case(
+    r#"{"lv_name":"full_snap", "vg_name":"vg0", "lv_attr":"swi-I-s---", "lv_size":"1.00g", "pool_lv":"", "origin":"data", "data_percent":"100.00", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""}"#,
+    Some(OrphanReason::DetachedSnapshot)
+)]
+#[case(
+    r#"{"lv_name":"inactive_snap", "vg_name":"vg0", "lv_attr":"Swi---s---", "lv_size":"1.00g", "pool_lv":"", "origin":"data", "data_percent":"", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""}"#,
+    Some(OrphanReason::DetachedSnapshot)
+)]
+#[case(
+    r#"{"lv_name":"broken", "vg_name":"vg0", "lv_attr":"-wi-----p-", "lv_size":"2.00g", "pool_lv":"", "origin":"", "data_percent":"", "metadata_percent":"", "move_pv":"", "mirror_log":"", "copy_percent":"", "convert_lv":""}"#,
+    Some(OrphanReason::Other("some PVs are missing".to_string()))
+)]
+fn orphan_reason(#[case] row: &str, #[case] expected: Option<OrphanReason>) {
+    let lv: LvInfo = serde_json::from_str(row).unwrap();
+    assert_eq!(OrphanReason::of(&lv), expected);
+}
@@ -0,0 +1,49 @@
+use lvm_sys2::lvm::{CommandRetCode, Lvm};
+use rstest::rstest;
+
+#[rstest]
+#[case("vg0", &[])]
+#[case("vg0", &[""])]
+#[case("vg0", &["a b"])]
+#[case("vg0", &["ok", "it's"])]
+#[case("vg0", &["@tag"])]
+#[case("vg 0", &["ok"])]
+fn invalid_tags_are_rejected(#[case] vg: &str, #[case] tags: &[&str]) {
+    assert_eq!(
+        Lvm::vg_tags_add(vg, tags),
+        Err(CommandRetCode::InvalidParameters)
+    );
+    assert_eq!(
+        Lvm::lv_tags_remove(vg, "data", tags),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
+
+#[test]
+fn long_tag_is_rejected() {
+    assert_eq!(
+        Lvm::vg_tags_add("vg0", &[&"t".repeat(129)]),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
+
+#[test]
+fn lvs_by_invalid_tag() {
+    assert_eq!(
+        Lvm::lvs_by_tag("a,b").unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[test]
+fn tags_of_invalid_names() {
+    assert_eq!(
+        Lvm::lv_tags("vg0", "da ta"),
+        Err(CommandRetCode::InvalidParameters)
+    );
+    assert_eq!(Lvm::vg_tags("vg 0"), Err(CommandRetCode::InvalidParameters));
+    assert_eq!(
+        Lvm::lvs_with_tag("").unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
@@ -0,0 +1,25 @@
+use lvm_sys2::{
+    lvm::{CommandRetCode, Lvm},
+    thin::MAX_THIN_LV_SIZE,
+    units::ByteSize,
+};
+use rstest::rstest;
+
+#[rstest]
+#[case(ByteSize(32 << 10))]
+#[case(ByteSize(96 << 10))]
+#[case(ByteSize(2 << 30))]
+fn invalid_chunk_size(#[case] chunk: ByteSize) {
+    assert_eq!(
+        Lvm::thin().create_pool("vg0", "pool", ByteSize(1 << 30), Some(chunk)),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
+
+#[test]
+fn too_big_thin_lv() {
+    assert_eq!(
+        Lvm::thin().create_thin_lv("vg0", "pool", "thin", ByteSize(MAX_THIN_LV_SIZE.0 + 1)),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
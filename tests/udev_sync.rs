@@ -0,0 +1,33 @@
+// own test binary: udev sync is configured once per process
+use lvm_sys2::lvm::{CommandRetCode, Lvm};
+
+#[test]
+fn udev_sync_is_configured_once() {
+    assert_eq!(Lvm::udev_sync(), None);
+    unsafe { Lvm::disable_udev_sync() }.unwrap();
+    assert_eq!(Lvm::udev_sync(), Some(false));
+    assert_eq!(
+        std::env::var("LVM_SUPPRESS_FD_WARNINGS").as_deref(),
+        Ok("1")
+    );
+    assert_eq!(
+        unsafe { Lvm::set_udev_sync(true) },
+        Err(CommandRetCode::AlreadyInitialized)
+    );
+    assert_eq!(Lvm::udev_sync(), Some(false));
+
+    #[cfg(feature = "test-utils")]
+    {
+        use lvm_sys2::mock::{MockLvm, VGS_FIXTURE};
+
+        let path = std::env::temp_dir().join(format!("lvm-sys2-udev-{}.conf", std::process::id()));
+        std::fs::write(&path, "devices/scan_lvs=0\n").unwrap();
+        let _mock = MockLvm::new().respond(
+            "vgs --config 'activation/udev_sync=0 devices/scan_lvs=0'",
+            Ok(VGS_FIXTURE.to_string()),
+        );
+        let res = Lvm::run_with_config("vgs", &path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(res.unwrap().contains_key("report"));
+    }
+}
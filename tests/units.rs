@@ -0,0 +1,63 @@
+use lvm_sys2::units::ByteSize;
+use rstest::rstest;
+
+#[rstest]
+#[case("<10.00g", 10 * 1024 * 1024 * 1024)]
+#[case("512.00m", 512 * 1024 * 1024)]
+#[case("0 ", 0)]
+#[case("4.00m", 4 * 1024 * 1024)]
+#[case("10737418240B", 10737418240)]
+#[case("1024", 1024)]
+fn parse_byte_size(#[case] input: &str, #[case] bytes: u64) {
+    assert_eq!(input.parse::<ByteSize>().unwrap(), ByteSize(bytes));
+}
+
+#[rstest]
+#[case("")]
+#[case("ten")]
+#[case("10.00x")]
+#[case("-1.00g")]
+fn parse_byte_size_invalid(#[case] input: &str) {
+    assert!(input.parse::<ByteSize>().is_err());
+}
+
+#[rstest]
+#[case(ByteSize(0), "0b")]
+#[case(ByteSize(512), "512b")]
+#[case(ByteSize::from_mib(512), "512.00m")]
+#[case(ByteSize::from_gib(10) - ByteSize::from_mib(20), "9.98g")]
+#[case(ByteSize::from_tib(1) + ByteSize::from_gib(512), "1.50t")]
+fn display_byte_size(#[case] size: ByteSize, #[case] expected: &str) {
+    assert_eq!(size.to_string(), expected);
+}
+
+#[test]
+fn byte_size_order() {
+    assert!(ByteSize::from_kib(1025) > ByteSize::from_mib(1));
+    assert_eq!(ByteSize::from_pib(1), ByteSize::from_tib(1024));
+}
+
+#[test]
+fn byte_size_arithmetic_saturates() {
+    assert_eq!(ByteSize::from_mib(1) - ByteSize::from_gib(1), ByteSize(0));
+    assert_eq!(ByteSize(u64::MAX) + ByteSize(1), ByteSize(u64::MAX));
+}
+
+#[rstest]
+#[case(ByteSize::from_kib)]
+#[case(ByteSize::from_mib)]
+#[case(ByteSize::from_gib)]
+#[case(ByteSize::from_tib)]
+#[case(ByteSize::from_pib)]
+#[should_panic(expected = "size overflows u64 bytes")]
+fn byte_size_overflow_panics(#[case] from: fn(u64) -> ByteSize) {
+    from(u64::MAX >> 9);
+}
+
+#[test]
+fn byte_size_upper_bound() {
+    assert_eq!(
+        ByteSize::from_pib(u64::MAX >> 50),
+        ByteSize(u64::MAX >> 50 << 50)
+    );
+}
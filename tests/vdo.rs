@@ -0,0 +1,33 @@
+use lvm_sys2::{
+    lv::LvCreateOptions,
+    lvm::CommandRetCode,
+    units::ByteSize,
+    vdo::{MAX_VDO_VIRTUAL_SIZE, VdoLvOptions},
+};
+use rstest::rstest;
+
+#[test]
+fn vdo_command() {
+    let vdo = VdoLvOptions::new("vpool", ByteSize::from_gib(1), ByteSize::from_gib(10))
+        .deduplication(false);
+    assert_eq!(
+        LvCreateOptions::new("vg0").vdo(vdo).command().unwrap(),
+        "lvcreate --type vdo -L 1073741824b -V 10737418240b --compression y --deduplication n 'vg0/vpool'"
+    );
+}
+
+#[rstest]
+#[case(VdoLvOptions::new("vpool", ByteSize::from_gib(1), MAX_VDO_VIRTUAL_SIZE + ByteSize(1)))]
+#[case(VdoLvOptions::new("vpool_tdata", ByteSize::from_gib(1), ByteSize::from_gib(10)))]
+#[case(VdoLvOptions::new("v pool", ByteSize::from_gib(1), ByteSize::from_gib(10)))]
+#[case(VdoLvOptions::new("vpool", ByteSize(0), ByteSize::from_gib(10)))]
+fn invalid_vdo(#[case] vdo: VdoLvOptions) {
+    assert_eq!(
+        LvCreateOptions::new("vg0")
+            .name("vdo0")
+            .vdo(vdo)
+            .command()
+            .unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
@@ -0,0 +1,25 @@
+use lvm_sys2::version::LvmVersion;
+use rstest::rstest;
+
+#[rstest]
+#[case("2.03.30(2)", LvmVersion::new(2, 3, 30))]
+#[case(" 2.02.99 ", LvmVersion::new(2, 2, 99))]
+#[case("2.03.16(2) (2022-05-18)", LvmVersion::new(2, 3, 16))]
+fn parse_version(#[case] input: &str, #[case] expected: LvmVersion) {
+    assert_eq!(input.parse::<LvmVersion>().unwrap(), expected);
+}
+
+#[rstest]
+#[case("")]
+#[case("2.03")]
+#[case("2.03.x")]
+#[case("2.03.30.1")]
+fn parse_invalid_version(#[case] input: &str) {
+    assert!(input.parse::<LvmVersion>().is_err());
+}
+
+#[test]
+fn version_order_and_display() {
+    assert!(LvmVersion::new(2, 3, 0) > LvmVersion::new(2, 2, 99));
+    assert_eq!(LvmVersion::new(2, 3, 5).to_string(), "2.03.05");
+}
@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use lvm_sys2::{
+    error::NameValidationError,
+    lvm::{CommandRetCode, Lvm},
+    vg::vg_validate_name,
+};
+use rstest::rstest;
+
+#[rstest]
+#[case("")]
+#[case("-vg")]
+#[case("..")]
+#[case("vg/0")]
+#[case("vg 0")]
+#[case("vg;ls")]
+#[case(&"v".repeat(129))]
+fn invalid_names_are_rejected(#[case] name: &str) {
+    let dev = Path::new("/dev/null");
+    assert!(matches!(
+        Lvm::vg().vgcreate(name, &[dev]),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+    assert!(matches!(
+        Lvm::vg().vgrename("vg0", name),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+    assert_eq!(
+        Lvm::vg_free_space(name),
+        Err(CommandRetCode::InvalidParameters)
+    );
+}
+
+#[test]
+fn devices_are_required() {
+    assert!(matches!(
+        Lvm::vg().vgextend("vg0", &[]),
+        Err(CommandRetCode::InvalidParameters)
+    ));
+}
+
+#[test]
+fn export_import_validate_arguments() {
+    assert_eq!(
+        Lvm::vg_export("vg/0").unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    assert_eq!(
+        Lvm::vg_import("vg0", &[]).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    assert_eq!(
+        Lvm::vg_import("vg0", &[Path::new("/dev/no-such-device")]).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[test]
+fn metadata_backup_requires_existing_dir() {
+    assert_eq!(
+        Lvm::metadata_backup("vg0", Path::new("/no/such/dir/vg0.backup")).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    assert_eq!(
+        Lvm::metadata_backup("vg/0", &std::env::temp_dir().join("vg0.backup")).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[test]
+fn metadata_restore_requires_existing_file() {
+    assert_eq!(
+        Lvm::metadata_restore("vg0", Path::new("/no/such/vg0.backup"), true).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[test]
+fn metadata_restore_requires_force() {
+    let backup = std::env::temp_dir().join(format!("lvm-sys2-vg0-{}.backup", std::process::id()));
+    std::fs::write(&backup, "").unwrap();
+    let res = Lvm::metadata_restore("vg0", &backup, false);
+    std::fs::remove_file(&backup).unwrap();
+    assert_eq!(res, Err(CommandRetCode::InvalidParameters));
+}
+
+#[test]
+fn split_merge_validate_arguments() {
+    let dev = Path::new("/dev/null");
+    assert_eq!(
+        Lvm::vg_split("vg0", "vg 1", &[dev]).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    assert_eq!(
+        Lvm::vg_split("vg0", "vg1", &[]).unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+    assert_eq!(
+        Lvm::vg_merge("vg0", "vg0").unwrap_err(),
+        CommandRetCode::InvalidParameters
+    );
+}
+
+#[rstest]
+#[case("vg0", Ok(()))]
+#[case("a+b.c-d_e", Ok(()))]
+#[case("", Err(NameValidationError::Empty))]
+#[case(&"v".repeat(129), Err(NameValidationError::TooLong { max: 128, actual: 129 }))]
+#[case("vg 0/x", Err(NameValidationError::InvalidChars(" /".to_string())))]
+#[case("-vg", Err(NameValidationError::InvalidChars("-".to_string())))]
+#[case("..", Err(NameValidationError::ReservedWord("..")))]
+fn vg_name_validation(#[case] name: &str, #[case] expected: Result<(), NameValidationError>) {
+    assert_eq!(vg_validate_name(name), expected);
+}